@@ -0,0 +1,5 @@
+use `rust-kata-001`;
+
+alter table crate_dependency
+    add kind     varchar(8) charset utf8 not null default 'normal',
+    add optional tinyint(1)              not null default 0;
@@ -0,0 +1,5 @@
+use `rust-kata-001`;
+
+alter table crate
+    add license  varchar(255) charset utf8 null,
+    add checksum char(64) charset utf8     null;
@@ -0,0 +1,4 @@
+use `rust-kata-001`;
+
+alter table crate_dependency
+    add req varchar(255) charset utf8 null after version;
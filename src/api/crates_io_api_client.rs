@@ -25,6 +25,19 @@ impl<'a> CratesIoApiClient<'a> {
         Ok(dto)
     }
 
+    /// Gets a version of a crate.
+    pub(crate) async fn version(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<CrateVersionApiDto, String> {
+        let url = format!("https://crates.io/api/v1/crates/{}/{}", name, version);
+
+        let dto = self.get("version", &url).await?;
+
+        Ok(dto)
+    }
+
     /// Gets the versions of a crate.
     pub(crate) async fn versions(&self, name: &str) -> Result<VersionsApiDto, String> {
         let url = format!("https://crates.io/api/v1/crates/{}", name);
@@ -88,9 +101,17 @@ pub(crate) struct VersionsApiDto {
     pub(crate) errors: Option<Vec<ErrorApiDto>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct CrateVersionApiDto {
+    pub(crate) version: Option<VersionApiDto>,
+    pub(crate) errors: Option<Vec<ErrorApiDto>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct VersionApiDto {
     pub(crate) num: String,
+    pub(crate) license: Option<String>,
+    pub(crate) checksum: Option<String>,
 }
 
 #[cfg(test)]
@@ -140,6 +161,26 @@ mod tests {
         Ok(())
     }
 
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_version() -> Result<(), String> {
+        let client = http_client_pool::new()?;
+        let client = CratesIoApiClient::new(&client);
+
+        let result = client.version("quote", "1.0.7").await?;
+
+        assert!(result.errors.is_none());
+        assert!(result.version.is_some());
+
+        let version = result.version.unwrap();
+
+        assert_eq!(version.num, "1.0.7");
+        assert_eq!(version.license.as_deref(), Some("MIT OR Apache-2.0"));
+        assert!(version.checksum.is_some());
+
+        Ok(())
+    }
+
     #[actix_rt::test]
     #[ignore]
     async fn integration_versions() -> Result<(), String> {
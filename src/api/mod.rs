@@ -1,18 +1,25 @@
 mod crates_io_api_client;
 
 use crate::api::crates_io_api_client::{CratesIoApiClient, DependencyApiDto};
-use crate::domain::{Crate, CrateDependency};
+use crate::concurrency::{Limiter, ResolutionLimiter};
+use crate::domain::{Crate, CrateDependency, CrateMetadata, DependencyKind};
+use futures::{StreamExt, TryStreamExt};
 use semver::Version;
 use std::collections::HashMap;
 
 pub(crate) struct Api<'a> {
     crates_io_api_client: CratesIoApiClient<'a>,
+    /// Every crates.io request of the resolution takes a permit, so nested lookups share its limit.
+    limiter: ResolutionLimiter<'a>,
+    concurrency: usize,
 }
 
 impl<'a> Api<'a> {
-    pub(crate) fn new(http_client_pool: &'a reqwest::Client) -> Api<'a> {
+    pub(crate) fn new(http_client_pool: &'a reqwest::Client, limiter: &'a Limiter) -> Api<'a> {
         Api {
             crates_io_api_client: CratesIoApiClient::new(http_client_pool),
+            limiter: limiter.resolution_limiter(),
+            concurrency: limiter.resolution(),
         }
     }
 
@@ -21,8 +28,11 @@ impl<'a> Api<'a> {
         let fn_name = "get_crate";
 
         let dto = self
-            .crates_io_api_client
-            .dependencies(name, &version.to_string())
+            .limiter
+            .run(
+                self.crates_io_api_client
+                    .dependencies(name, &version.to_string()),
+            )
             .await?;
 
         if let Some(e) = dto.errors {
@@ -36,35 +46,97 @@ impl<'a> Api<'a> {
         })?;
 
         let crate_dependencies =
-            futures::future::join_all(dependencies.iter().filter_map(|dependency| {
+            futures::stream::iter(dependencies.iter().filter_map(|dependency| {
                 if dependency.kind == "normal" {
                     Some(self.convert_or_best_guess(dependency))
                 } else {
                     None
                 }
             }))
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
+            .buffer_unordered(self.concurrency)
+            .try_collect::<Vec<_>>()
+            .await?;
 
         let mut results = HashMap::new();
 
+        // a crate depended on more than once is optional only if all of its uses are.
         for crate_dependency in crate_dependencies.iter() {
-            results
+            let result = results
                 .entry((&crate_dependency.name, &crate_dependency.version))
-                .or_insert(CrateDependency {
-                    name: crate_dependency.name.to_owned(),
-                    version: crate_dependency.version.to_owned(),
-                });
+                .or_insert_with(|| crate_dependency.clone());
+
+            result.optional &= crate_dependency.optional;
         }
 
         Ok(Crate {
             name: name.to_owned(),
             version: version.to_owned(),
             dependency: results.into_iter().map(|e| e.1).collect(),
+            // only looked up when needed, see `get_metadata`.
+            metadata: CrateMetadata::default(),
         })
     }
 
+    /// Gets the published metadata of a crate.
+    pub(crate) async fn get_metadata(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> Result<CrateMetadata, String> {
+        let fn_name = "get_metadata";
+
+        let dto = self
+            .limiter
+            .run(
+                self.crates_io_api_client
+                    .version(name, &version.to_string()),
+            )
+            .await?;
+
+        if let Some(e) = dto.errors {
+            log::error!("{}: crates.io client error {:?}", fn_name, e);
+            return Err(format!("{}: crates.io client error: {:?}", fn_name, e));
+        }
+
+        let version = dto.version.ok_or_else(|| {
+            log::error!("{}: crates.io contract violation", fn_name);
+            format!("{}: crates.io contract violation", fn_name)
+        })?;
+
+        Ok(CrateMetadata {
+            license: version.license,
+            checksum: version.checksum,
+        })
+    }
+
+    /// Every published version of the crate.
+    pub(crate) async fn get_versions(&self, name: &str) -> Result<Vec<Version>, String> {
+        let fn_name = "get_versions";
+
+        let dto = self
+            .limiter
+            .run(self.crates_io_api_client.versions(name))
+            .await?;
+
+        if let Some(e) = dto.errors {
+            log::error!("{}: crates.io client error {:?}", fn_name, e);
+            return Err(format!("{}: crates.io client error: {:?}", fn_name, e));
+        }
+
+        dto.versions
+            .ok_or_else(|| {
+                log::error!("{}: crates.io contract violation", fn_name);
+                format!("{}: crates.io contract violation", fn_name)
+            })?
+            .iter()
+            .map(|version| Version::parse(&version.num))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                log::error!("{}: sem ver error {:?}", fn_name, e);
+                format!("{}: sem ver error: {:?}", fn_name, e)
+            })
+    }
+
     async fn convert_or_best_guess(
         &self,
         dependency: &DependencyApiDto,
@@ -88,6 +160,9 @@ impl<'a> Api<'a> {
             Ok(Some(CrateDependency {
                 name: dependency.crate_id.to_owned(),
                 version,
+                req: Some(dependency.req.to_owned()),
+                kind: Self::kind(dependency)?,
+                optional: dependency.optional,
             }))
         } else {
             Ok(None)
@@ -95,33 +170,9 @@ impl<'a> Api<'a> {
     }
 
     async fn best_guess(&self, dependency: &DependencyApiDto) -> Result<CrateDependency, String> {
-        let fn_name = "best_guess";
-
         let version_reqs = Self::parse_requirements(&dependency.req)?;
 
-        let dto = self
-            .crates_io_api_client
-            .versions(&dependency.crate_id)
-            .await?;
-
-        if let Some(e) = dto.errors {
-            log::error!("{}: crates.io client error {:?}", fn_name, e);
-            return Err(format!("{}: crates.io client error: {:?}", fn_name, e));
-        }
-
-        let versions = dto
-            .versions
-            .ok_or_else(|| {
-                log::error!("{}: crates.io contract violation", fn_name);
-                format!("{}: crates.io contract violation", fn_name)
-            })?
-            .iter()
-            .map(|version| Version::parse(&version.num))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| {
-                log::error!("{}: sem ver error {:?}", fn_name, e);
-                format!("{}: sem ver error: {:?}", fn_name, e)
-            })?;
+        let versions = self.get_versions(&dependency.crate_id).await?;
 
         let mut matching_versions = versions
             .iter()
@@ -134,6 +185,18 @@ impl<'a> Api<'a> {
         Ok(CrateDependency {
             name: dependency.crate_id.to_owned(),
             version: best_version.to_owned(),
+            req: Some(dependency.req.to_owned()),
+            kind: Self::kind(dependency)?,
+            optional: dependency.optional,
+        })
+    }
+
+    fn kind(dependency: &DependencyApiDto) -> Result<DependencyKind, String> {
+        let fn_name = "kind";
+
+        DependencyKind::parse(&dependency.kind).ok_or_else(|| {
+            log::error!("{}: unknown kind {:?}", fn_name, dependency.kind);
+            format!("{}: unknown kind: {:?}", fn_name, dependency.kind)
         })
     }
 
@@ -215,7 +278,8 @@ mod tests {
     #[ignore]
     async fn integration_dependencies() -> Result<(), String> {
         let client = http_client_pool::new()?;
-        let client = Api::new(&client);
+        let limiter = Limiter::new(1, 1);
+        let client = Api::new(&client, &limiter);
 
         let c = client
             .get_crate("time", &semver::Version::parse("0.2.22").unwrap())
@@ -264,7 +328,8 @@ mod tests {
     #[ignore]
     async fn integration_edge_case_multiple_versions() -> Result<(), String> {
         let client = http_client_pool::new()?;
-        let client = Api::new(&client);
+        let limiter = Limiter::new(1, 1);
+        let client = Api::new(&client, &limiter);
 
         let c = client
             .get_crate("yaml-rust", &semver::Version::parse("0.3.5").unwrap())
@@ -0,0 +1,105 @@
+use crate::cache::MemoryCache;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Redis channel every instance publishes its invalidations to.
+pub(crate) const CHANNEL: &str = "cache:invalidations";
+
+/// Wait before subscribing again after the subscription dropped.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// Entries to drop from the memory of every instance.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum InvalidationDto {
+    Keys(Vec<String>),
+    /// Everything, for invalidations not matched in memory.
+    Clear,
+}
+
+/// Applies the invalidations published by every instance to the memory of this one, so none
+/// serves entries invalidated by another. Subscribes again whenever the subscription drops.
+pub(crate) async fn subscribe(redis_url: String, memory: Arc<MemoryCache>) {
+    let fn_name = "subscribe";
+
+    loop {
+        if let Err(error) = listen(&redis_url, &memory).await {
+            log::error!("{}: error={:?}", fn_name, error);
+        }
+
+        // invalidations published while unsubscribed are lost.
+        memory.clear();
+
+        actix_web::rt::time::delay_for(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+async fn listen(redis_url: &str, memory: &MemoryCache) -> Result<(), String> {
+    let fn_name = "listen";
+
+    let map_error = |error: redis::RedisError| {
+        log::error!("{}: RedisError={:?}", fn_name, error);
+        format!("{}: RedisError={:?}", fn_name, error)
+    };
+
+    let mut pubsub = redis::Client::open(redis_url)
+        .map_err(map_error)?
+        .get_tokio_connection()
+        .await
+        .map_err(map_error)?
+        .into_pubsub();
+
+    pubsub.subscribe(CHANNEL).await.map_err(map_error)?;
+
+    let messages = pubsub.on_message();
+    futures::pin_mut!(messages);
+
+    while let Some(message) = messages.next().await {
+        let payload: String = message.get_payload().map_err(map_error)?;
+
+        apply(memory, &payload);
+    }
+
+    Err(format!("{}: subscription closed", fn_name))
+}
+
+fn apply(memory: &MemoryCache, payload: &str) {
+    let fn_name = "apply";
+
+    match serde_json::from_str(payload) {
+        Ok(InvalidationDto::Keys(keys)) => memory.remove(&keys),
+        Ok(InvalidationDto::Clear) => memory.clear(),
+        Err(error) => log::warn!("{}: payload={:?} Error={:?}", fn_name, payload, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn applies_invalidations() {
+        let memory = MemoryCache::new(10);
+        memory.insert("a".to_owned(), vec![node("a", "1.0.0", &[])], None);
+        memory.insert("b".to_owned(), vec![node("b", "1.0.0", &[])], None);
+        memory.insert("c".to_owned(), vec![node("c", "1.0.0", &[])], None);
+
+        let keys = InvalidationDto::Keys(vec!["a".to_owned(), "d".to_owned()]);
+        apply(&memory, &serde_json::to_string(&keys).unwrap());
+
+        assert!(memory.get("a").is_none());
+        assert!(memory.get("b").is_some());
+
+        apply(&memory, "not an invalidation");
+        assert!(memory.get("b").is_some());
+
+        apply(
+            &memory,
+            &serde_json::to_string(&InvalidationDto::Clear).unwrap(),
+        );
+        assert_eq!(memory.size(), (0, 0));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::domain::Crate;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Least recently used crates and graphs, kept in process in front of Redis and shared by every
+/// worker. Bounded by the number of crates held, a graph weighing as many crates as it holds.
+pub(crate) struct MemoryCache {
+    capacity: usize,
+    lru: Mutex<Lru>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    evictions: AtomicUsize,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, Entry>,
+    /// Keys by the tick they were last used at, least recent first.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    weight: usize,
+}
+
+struct Entry {
+    /// Shared with the readers, so a hit is not a deep copy under the lock.
+    crates: Arc<Vec<Crate>>,
+    expires: Option<Instant>,
+    tick: u64,
+}
+
+impl MemoryCache {
+    /// A capacity of 0 disables the cache.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lru: Mutex::new(Lru::default()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+            evictions: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Maximum number of crates held.
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Number of entries dropped to make room for others.
+    pub(crate) fn evictions(&self) -> usize {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Number of entries and of crates held.
+    pub(crate) fn size(&self) -> (usize, usize) {
+        let lru = self.lock();
+        (lru.entries.len(), lru.weight)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Arc<Vec<Crate>>> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Holds the crates under the key for `ttl`, for ever when `None`.
+    pub(crate) fn insert(&self, key: String, crates: Vec<Crate>, ttl: Option<Duration>) {
+        self.insert_at(key, crates, ttl, Instant::now())
+    }
+
+    pub(crate) fn remove(&self, keys: &[String]) {
+        if !self.enabled() {
+            return;
+        }
+
+        let mut lru = self.lock();
+        for key in keys {
+            lru.remove(key);
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        *self.lock() = Lru::default();
+    }
+
+    /// Nothing panics while the lock is held, and entries are whole between calls regardless, so
+    /// a poisoned lock is recovered rather than propagated to every later caller.
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        self.lru.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<Arc<Vec<Crate>>> {
+        if !self.enabled() {
+            return None;
+        }
+
+        let mut lru = self.lock();
+
+        let expired = match lru.entries.get(key) {
+            Some(entry) => matches!(entry.expires, Some(expires) if expires <= now),
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        if expired {
+            lru.remove(key);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        lru.touch(key)
+    }
+
+    fn insert_at(&self, key: String, crates: Vec<Crate>, ttl: Option<Duration>, now: Instant) {
+        // an entry heavier than the whole cache would only evict everything else.
+        if !self.enabled() || crates.len() > self.capacity || ttl == Some(Duration::from_secs(0)) {
+            return;
+        }
+
+        let crates = Arc::new(crates);
+
+        let mut lru = self.lock();
+
+        lru.remove(&key);
+
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.weight += crates.len();
+        lru.recency.insert(tick, key.to_owned());
+        lru.entries.insert(
+            key,
+            Entry {
+                crates,
+                expires: ttl.map(|ttl| now + ttl),
+                tick,
+            },
+        );
+
+        while lru.weight > self.capacity {
+            let oldest = match lru.recency.values().next() {
+                Some(key) => key.to_owned(),
+                None => break,
+            };
+            lru.remove(&oldest);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Lru {
+    /// Marks the entry as the most recently used, returning its crates.
+    fn touch(&mut self, key: &str) -> Option<Arc<Vec<Crate>>> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.tick);
+        self.recency.insert(tick, key.to_owned());
+        entry.tick = tick;
+
+        Some(Arc::clone(&entry.crates))
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.tick);
+            self.weight -= entry.crates.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = MemoryCache::new(3);
+        let now = Instant::now();
+
+        cache.insert_at("a".to_owned(), vec![node("a", "1.0.0", &[])], None, now);
+        cache.insert_at(
+            "b".to_owned(),
+            vec![node("b", "1.0.0", &[]), node("c", "1.0.0", &[])],
+            None,
+            now,
+        );
+        assert!(cache.get_at("a", now).is_some());
+
+        // b is the least recently used, so makes room for d.
+        cache.insert_at("d".to_owned(), vec![node("d", "1.0.0", &[])], None, now);
+
+        assert!(cache.get_at("b", now).is_none());
+        assert_eq!(
+            cache.get_at("a", now),
+            Some(Arc::new(vec![node("a", "1.0.0", &[])]))
+        );
+        assert_eq!(cache.size(), (2, 2));
+        assert_eq!(cache.evictions(), 1);
+        assert_eq!((cache.hits(), cache.misses()), (2, 1));
+    }
+
+    #[test]
+    fn expires_with_ttl() {
+        let cache = MemoryCache::new(10);
+        let now = Instant::now();
+
+        cache.insert_at(
+            "a".to_owned(),
+            vec![node("a", "1.0.0", &[])],
+            Some(Duration::from_secs(60)),
+            now,
+        );
+        cache.insert_at(
+            "b".to_owned(),
+            vec![node("b", "1.0.0", &[])],
+            Some(Duration::from_secs(0)),
+            now,
+        );
+
+        assert!(cache.get_at("a", now + Duration::from_secs(59)).is_some());
+        assert!(cache.get_at("a", now + Duration::from_secs(60)).is_none());
+        assert!(cache.get_at("b", now).is_none());
+        assert_eq!(cache.size(), (0, 0));
+    }
+
+    #[test]
+    fn disabled_with_zero_capacity() {
+        let cache = MemoryCache::new(0);
+
+        cache.insert("a".to_owned(), vec![node("a", "1.0.0", &[])], None);
+
+        assert!(cache.get("a").is_none());
+        assert_eq!((cache.hits(), cache.misses()), (0, 0));
+    }
+}
@@ -1,16 +1,52 @@
+mod invalidations;
+mod memory_cache;
 mod redis_cache;
 
+use crate::cache::invalidations::InvalidationDto;
 use crate::cache::redis_cache::RedisCache;
-use crate::domain::{Crate, CrateDependency};
+use crate::domain::{
+    Crate, CrateDependency, CrateMetadata, DependencyKind, Job, JobProgress, JobStatus,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
 
+pub(crate) use invalidations::subscribe;
+pub(crate) use memory_cache::MemoryCache;
+
+/// Version of the format of cached payloads, to be bumped whenever it changes so entries written
+/// by other releases are discarded rather than misread.
+const SCHEMA_VERSION: u32 = 1;
+
+/// What is cached and for how long, expiries in seconds with 0 keeping entries until evicted.
+#[derive(Clone, Debug)]
+pub(crate) struct CacheConfig {
+    /// Whether whole graphs are cached, on top of the crates they are assembled from.
+    pub(crate) dependencies_enabled: bool,
+    pub(crate) dependencies_ttl: u64,
+    pub(crate) nodes_ttl: u64,
+    pub(crate) jobs_ttl: u64,
+}
+
+/// Crates and graphs are looked up in memory then in Redis, jobs in Redis only as they are shared
+/// between processes.
 pub(crate) struct Cache<'a> {
+    memory: &'a MemoryCache,
     redis: RedisCache<'a>,
+    config: &'a CacheConfig,
 }
 
 impl<'a> Cache<'a> {
-    pub(crate) fn new(redis_pool: &'a redis::aio::MultiplexedConnection) -> Self {
+    pub(crate) fn new(
+        redis_pool: &'a redis::aio::MultiplexedConnection,
+        memory_cache: &'a MemoryCache,
+        config: &'a CacheConfig,
+    ) -> Self {
         Cache {
+            memory: memory_cache,
             redis: RedisCache::new(redis_pool),
+            config,
         }
     }
 
@@ -21,17 +57,44 @@ impl<'a> Cache<'a> {
     ) -> Result<Option<Vec<Crate>>, String> {
         let fn_name = "get_dependencies";
 
-        if let Some(json) = self
+        if !self.config.dependencies_enabled {
+            return Ok(None);
+        }
+
+        let key = Self::get_dependencies_key(name, version);
+
+        if let Some(crates) = self.memory.get(&key) {
+            return Ok(Some(crates.to_vec()));
+        }
+
+        if let Some(Some((value, ttl))) = self
             .redis
-            .get_string(&Self::get_dependencies_key(name, version))
+            .get_strings_with_ttl(&[key.to_owned()])
             .await?
+            .pop()
         {
-            let result = serde_json::from_str::<Vec<CrateDto>>(&json).map_err(|error| {
-                log::error!("{}: Error={:?}", fn_name, error);
-                format!("{}: Error={:?}", fn_name, error)
-            })?;
+            let result = value
+                .and_then(|json| decode::<Vec<CrateDto>>(&json))
+                .and_then(|result| {
+                    result
+                        .iter()
+                        .map(CrateDto::into)
+                        .collect::<Result<Vec<_>, _>>()
+                });
+
+            match result {
+                Ok(crates) => {
+                    // kept in memory for as long as Redis keeps it.
+                    self.memory.insert(key, crates.clone(), ttl);
 
-            Ok(Some(result.iter().map(CrateDto::into).collect()))
+                    Ok(Some(crates))
+                }
+                Err(error) => {
+                    self.discard(fn_name, &[(key, error)]).await;
+
+                    Ok(None)
+                }
+            }
         } else {
             Ok(None)
         }
@@ -45,23 +108,314 @@ impl<'a> Cache<'a> {
     ) -> Result<(), String> {
         let fn_name = "save_dependencies";
 
+        if !self.config.dependencies_enabled {
+            return Ok(());
+        }
+
         let dto = crates.iter().map(CrateDto::from).collect::<Vec<_>>();
 
-        let result = serde_json::to_string(&dto).map_err(|error| {
+        let result = encode(&dto).map_err(|error| {
             log::error!("{}: Error={:?}", fn_name, error);
             format!("{}: Error={:?}", fn_name, error)
         })?;
 
+        let key = Self::get_dependencies_key(name, version);
+
+        self.redis
+            .set_string(&key, &result, self.config.dependencies_ttl)
+            .await?;
+
+        self.memory.insert(
+            key.to_owned(),
+            crates.to_vec(),
+            Self::ttl(self.config.dependencies_ttl),
+        );
+
+        // indexed by every crate of the graph, so refreshing any of them invalidates it.
+        let graphs_keys = crates
+            .iter()
+            .map(|c| Self::get_graphs_key(&c.name, &c.version))
+            .collect::<Vec<_>>();
+
         self.redis
-            .set_string(&Self::get_dependencies_key(name, version), &result)
+            .add_to_sets(&graphs_keys, &key, self.config.dependencies_ttl)
             .await?;
 
         Ok(())
     }
 
+    /// Looks crates up in memory, then in Redis with a single `MGET`, returning those cached.
+    pub(crate) async fn get_nodes(
+        &self,
+        name_versions: &[(String, semver::Version)],
+    ) -> Result<HashMap<(String, semver::Version), Crate>, String> {
+        let fn_name = "get_nodes";
+
+        let mut results = HashMap::new();
+        let mut misses = Vec::new();
+
+        for name_version in name_versions {
+            let key = Self::get_node_key(&name_version.0, &name_version.1);
+
+            match self
+                .memory
+                .get(&key)
+                .and_then(|crates| crates.first().cloned())
+            {
+                Some(c) => {
+                    results.insert(name_version.to_owned(), c);
+                }
+                None => misses.push((name_version, key)),
+            }
+        }
+
+        let keys = misses
+            .iter()
+            .map(|(_, key)| key.to_owned())
+            .collect::<Vec<_>>();
+
+        let mut corrupt = Vec::new();
+
+        for ((name_version, key), entry) in misses
+            .into_iter()
+            .zip(self.redis.get_strings_with_ttl(&keys).await?)
+        {
+            if let Some((value, ttl)) = entry {
+                match value
+                    .and_then(|json| decode::<CrateDto>(&json))
+                    .and_then(|result| CrateDto::into(&result))
+                {
+                    Ok(c) => {
+                        self.memory.insert(key, vec![c.clone()], ttl);
+                        results.insert(name_version.to_owned(), c);
+                    }
+                    Err(error) => corrupt.push((key, error)),
+                }
+            }
+        }
+
+        // resolved again, as if they were not cached.
+        self.discard(fn_name, &corrupt).await;
+
+        Ok(results)
+    }
+
+    /// Caches every crate with its direct dependencies, with a single pipeline.
+    pub(crate) async fn save_nodes(&self, crates: &[&Crate]) -> Result<(), String> {
+        let fn_name = "save_nodes";
+
+        let mut entries = Vec::new();
+
+        for c in crates {
+            let json = encode(&CrateDto::from(c)).map_err(|error| {
+                log::error!("{}: Error={:?}", fn_name, error);
+                format!("{}: Error={:?}", fn_name, error)
+            })?;
+
+            entries.push((Self::get_node_key(&c.name, &c.version), json));
+        }
+
+        self.redis
+            .set_strings(&entries, self.config.nodes_ttl)
+            .await?;
+
+        for (c, (key, _)) in crates.iter().zip(entries) {
+            self.memory
+                .insert(key, vec![(*c).clone()], Self::ttl(self.config.nodes_ttl));
+        }
+
+        Ok(())
+    }
+
+    /// Deletes crate+version and the cached graphs holding it, returning the number of keys
+    /// deleted.
+    pub(crate) async fn invalidate(
+        &self,
+        name: &str,
+        version: &semver::Version,
+    ) -> Result<usize, String> {
+        let graphs_key = Self::get_graphs_key(name, version);
+
+        let mut keys = self.redis.members(&graphs_key).await?;
+        keys.push(Self::get_node_key(name, version));
+        keys.push(Self::get_dependencies_key(name, version));
+        keys.push(graphs_key);
+        keys.sort();
+        keys.dedup();
+
+        self.memory.remove(&keys);
+
+        let deleted = self.redis.delete(&keys).await?;
+
+        self.broadcast(&InvalidationDto::Keys(keys)).await?;
+
+        Ok(deleted)
+    }
+
+    /// Deletes the crates whose `{name}:{version}` matches the glob style pattern and the cached
+    /// graphs rooted at or holding them, returning the number of keys deleted.
+    pub(crate) async fn purge(&self, pattern: &str) -> Result<usize, String> {
+        let graphs_keys = self.redis.scan(&format!("graphs:{}", pattern)).await?;
+
+        let mut keys = self
+            .redis
+            .scan(&format!("dependencies:{}", pattern))
+            .await?;
+        keys.append(&mut self.redis.scan(&format!("node:{}", pattern)).await?);
+        for graphs_key in &graphs_keys {
+            keys.append(&mut self.redis.members(graphs_key).await?);
+        }
+        keys.extend(graphs_keys);
+        keys.sort();
+        keys.dedup();
+
+        // patterns are not matched in memory, where everything goes instead.
+        self.memory.clear();
+
+        let deleted = self.redis.delete(&keys).await?;
+
+        self.broadcast(&InvalidationDto::Clear).await?;
+
+        Ok(deleted)
+    }
+
+    /// Has every instance drop the entries from its memory.
+    async fn broadcast(&self, invalidation: &InvalidationDto) -> Result<(), String> {
+        let fn_name = "broadcast";
+
+        let message = serde_json::to_string(invalidation).map_err(|error| {
+            log::error!("{}: Error={:?}", fn_name, error);
+            format!("{}: Error={:?}", fn_name, error)
+        })?;
+
+        self.redis.publish(invalidations::CHANNEL, &message).await
+    }
+
+    /// Deletes entries that cannot be read, so they are replaced on the next save instead of being
+    /// read again. Failing to delete them only means they are discarded again next time.
+    async fn discard(&self, fn_name: &str, entries: &[(String, String)]) {
+        if entries.is_empty() {
+            return;
+        }
+
+        for (key, error) in entries {
+            log::warn!("{}: discarding key={:?} Error={}", fn_name, key, error);
+        }
+
+        let keys = entries
+            .iter()
+            .map(|(key, _)| key.to_owned())
+            .collect::<Vec<_>>();
+
+        let _ = self.redis.delete(&keys).await;
+    }
+
+    /// Expiry in memory matching the expiry in Redis of an entry just set.
+    fn ttl(seconds: u64) -> Option<Duration> {
+        if seconds > 0 {
+            Some(Duration::from_secs(seconds))
+        } else {
+            None
+        }
+    }
+
     fn get_dependencies_key(name: &str, version: &semver::Version) -> String {
         format!("dependencies:{}:{}", name, version)
     }
+
+    fn get_node_key(name: &str, version: &semver::Version) -> String {
+        format!("node:{}:{}", name, version)
+    }
+
+    /// Set of the keys of the cached graphs holding crate+version.
+    fn get_graphs_key(name: &str, version: &semver::Version) -> String {
+        format!("graphs:{}:{}", name, version)
+    }
+
+    pub(crate) async fn next_job_id(&self) -> Result<String, String> {
+        Ok(self.redis.increment("jobs:id").await?.to_string())
+    }
+
+    pub(crate) async fn get_job(&self, id: &str) -> Result<Option<Job>, String> {
+        let fn_name = "get_job";
+
+        let key = Self::get_job_key(id);
+
+        let result = match self.redis.get_string(&key).await? {
+            Some(value) => value
+                .and_then(|json| decode::<JobDto>(&json))
+                .and_then(|result| JobDto::into(&result)),
+            None => return Ok(None),
+        };
+
+        match result {
+            Ok(job) => Ok(Some(job)),
+            Err(error) => {
+                self.discard(fn_name, &[(key, error)]).await;
+
+                Ok(None)
+            }
+        }
+    }
+
+    pub(crate) async fn save_job(&self, job: &Job) -> Result<(), String> {
+        let fn_name = "save_job";
+
+        let result = encode(&JobDto::from(job)).map_err(|error| {
+            log::error!("{}: Error={:?}", fn_name, error);
+            format!("{}: Error={:?}", fn_name, error)
+        })?;
+
+        self.redis
+            .set_string(&Self::get_job_key(&job.id), &result, self.config.jobs_ttl)
+            .await?;
+
+        Ok(())
+    }
+
+    fn get_job_key(id: &str) -> String {
+        format!("jobs:{}", id)
+    }
+}
+
+/// Escapes the characters special to Redis glob style patterns, so the value only matches itself.
+pub(crate) fn escape_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Cached payload, tagged with the version of its format.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Payload<T> {
+    schema: u32,
+    data: T,
+}
+
+fn encode<T: Serialize>(data: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&Payload {
+        schema: SCHEMA_VERSION,
+        data,
+    })
+}
+
+/// Decodes a cached payload, failing when it is corrupt or of another version of the format.
+fn decode<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    let payload = serde_json::from_str::<Payload<serde_json::Value>>(json)
+        .map_err(|error| format!("payload invalid: {:?}", error))?;
+
+    if payload.schema != SCHEMA_VERSION {
+        return Err(format!("schema unsupported: {}", payload.schema));
+    }
+
+    serde_json::from_value(payload.data).map_err(|error| format!("data invalid: {:?}", error))
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -69,6 +423,10 @@ struct CrateDto {
     name: String,
     version: String,
     dependency: Vec<CrateDependencyDto>,
+    #[serde(default)]
+    license: Option<String>,
+    #[serde(default)]
+    checksum: Option<String>,
 }
 
 impl CrateDto {
@@ -81,19 +439,25 @@ impl CrateDto {
                 .iter()
                 .map(CrateDependencyDto::from)
                 .collect(),
+            license: item.metadata.license.to_owned(),
+            checksum: item.metadata.checksum.to_owned(),
         }
     }
 
-    fn into(item: &Self) -> Crate {
-        Crate {
+    fn into(item: &Self) -> Result<Crate, String> {
+        Ok(Crate {
             name: item.name.to_owned(),
-            version: semver::Version::parse(&item.version).unwrap(),
+            version: parse_version(&item.version)?,
             dependency: item
                 .dependency
                 .iter()
                 .map(CrateDependencyDto::into)
-                .collect(),
-        }
+                .collect::<Result<_, _>>()?,
+            metadata: CrateMetadata {
+                license: item.license.to_owned(),
+                checksum: item.checksum.to_owned(),
+            },
+        })
     }
 }
 
@@ -101,6 +465,13 @@ impl CrateDto {
 struct CrateDependencyDto {
     name: String,
     version: String,
+    // graphs cached before kinds were recorded only hold normal, required dependencies.
+    #[serde(default = "CrateDependencyDto::normal")]
+    kind: String,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    req: Option<String>,
 }
 
 impl CrateDependencyDto {
@@ -108,13 +479,285 @@ impl CrateDependencyDto {
         Self {
             name: item.name.to_owned(),
             version: item.version.to_string(),
+            kind: item.kind.as_str().to_owned(),
+            optional: item.optional,
+            req: item.req.to_owned(),
+        }
+    }
+
+    fn into(item: &Self) -> Result<CrateDependency, String> {
+        Ok(CrateDependency {
+            name: item.name.to_owned(),
+            version: parse_version(&item.version)?,
+            req: item.req.to_owned(),
+            kind: DependencyKind::parse(&item.kind).unwrap_or(DependencyKind::Normal),
+            optional: item.optional,
+        })
+    }
+
+    fn normal() -> String {
+        DependencyKind::Normal.as_str().to_owned()
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JobDto {
+    id: String,
+    name: String,
+    version: String,
+    depth: Option<usize>,
+    status: String,
+    progress_depth: usize,
+    progress_crates: usize,
+    result: Option<Vec<CrateDto>>,
+    error: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl JobDto {
+    fn from(item: &Job) -> Self {
+        Self {
+            id: item.id.to_owned(),
+            name: item.name.to_owned(),
+            version: item.version.to_string(),
+            depth: item.depth,
+            status: match item.status {
+                JobStatus::Queued => "queued".to_owned(),
+                JobStatus::Running => "running".to_owned(),
+                JobStatus::Completed => "completed".to_owned(),
+                JobStatus::Failed => "failed".to_owned(),
+            },
+            progress_depth: item.progress.depth,
+            progress_crates: item.progress.crates,
+            result: item
+                .result
+                .as_ref()
+                .map(|crates| crates.iter().map(CrateDto::from).collect()),
+            error: item.error.to_owned(),
+            created_at: item.created_at.to_owned(),
+            updated_at: item.updated_at.to_owned(),
         }
     }
 
-    fn into(item: &Self) -> CrateDependency {
-        CrateDependency {
+    fn into(item: &Self) -> Result<Job, String> {
+        let fn_name = "into";
+
+        Ok(Job {
+            id: item.id.to_owned(),
             name: item.name.to_owned(),
-            version: semver::Version::parse(&item.version).unwrap(),
+            version: parse_version(&item.version)?,
+            depth: item.depth,
+            status: match item.status.as_str() {
+                "queued" => JobStatus::Queued,
+                "running" => JobStatus::Running,
+                "completed" => JobStatus::Completed,
+                "failed" => JobStatus::Failed,
+                status => {
+                    log::error!("{}: unknown status={:?}", fn_name, status);
+                    return Err(format!("{}: unknown status={:?}", fn_name, status));
+                }
+            },
+            progress: JobProgress {
+                depth: item.progress_depth,
+                crates: item.progress_crates,
+            },
+            result: item
+                .result
+                .as_ref()
+                .map(|crates| crates.iter().map(CrateDto::into).collect())
+                .transpose()?,
+            error: item.error.to_owned(),
+            created_at: item.created_at.to_owned(),
+            updated_at: item.updated_at.to_owned(),
+        })
+    }
+}
+
+fn parse_version(version: &str) -> Result<semver::Version, String> {
+    semver::Version::parse(version).map_err(|error| format!("version invalid: {:?}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory::redis_pool;
+    use crate::test_support::node;
+    use redis::AsyncCommands;
+
+    const REDIS_URL: &str = "redis://localhost:6379";
+
+    fn config() -> CacheConfig {
+        CacheConfig {
+            dependencies_enabled: true,
+            dependencies_ttl: 60,
+            nodes_ttl: 60,
+            jobs_ttl: 60,
         }
     }
+
+    /// Writes an entry from before payloads were versioned, one that is not utf8 and one of
+    /// another type.
+    async fn write_unreadable(
+        connection: &mut redis::aio::MultiplexedConnection,
+        keys: &[String; 3],
+    ) -> Result<(), String> {
+        let map_error =
+            |error: redis::RedisError| format!("write_unreadable: RedisError={:?}", error);
+
+        let _: () = connection.del(&keys[..]).await.map_err(map_error)?;
+        let _: () = connection
+            .set(
+                &keys[0],
+                r#"{"name":"a","version":"1.0.0","dependency":[]}"#,
+            )
+            .await
+            .map_err(map_error)?;
+        let _: () = connection
+            .set(&keys[1], vec![0xffu8, 0xfe])
+            .await
+            .map_err(map_error)?;
+        connection.sadd(&keys[2], "value").await.map_err(map_error)
+    }
+
+    async fn exists(
+        connection: &mut redis::aio::MultiplexedConnection,
+        keys: &[String],
+    ) -> Result<usize, String> {
+        connection
+            .exists(keys)
+            .await
+            .map_err(|error| format!("exists: RedisError={:?}", error))
+    }
+
+    #[test]
+    fn escape_pattern_globs() {
+        assert_eq!(escape_pattern("tokio:0.2.22"), "tokio:0.2.22");
+        assert_eq!(escape_pattern("a*b?[c]\\"), "a\\*b\\?\\[c\\]\\\\");
+    }
+
+    #[test]
+    fn decode_encoded() {
+        let c = node("a", "1.0.0", &[("b", "1.0.0")]);
+
+        let json = encode(&CrateDto::from(&c)).unwrap();
+        let result = decode::<CrateDto>(&json).and_then(|result| CrateDto::into(&result));
+
+        assert_eq!(result, Ok(c));
+    }
+
+    #[test]
+    fn decode_unreadable() {
+        // written before payloads were versioned.
+        assert!(decode::<CrateDto>(r#"{"name":"a","version":"1.0.0","dependency":[]}"#).is_err());
+        assert!(decode::<CrateDto>(
+            r#"{"schema":0,"data":{"name":"a","version":"1.0.0","dependency":[]}}"#
+        )
+        .is_err());
+        assert!(decode::<CrateDto>(r#"{"schema":1,"data":{"name":"a"}}"#).is_err());
+        assert!(decode::<CrateDto>("{").is_err());
+
+        let result = decode::<CrateDto>(
+            r#"{"schema":1,"data":{"name":"a","version":"1.0","dependency":[]}}"#,
+        )
+        .unwrap();
+        assert!(CrateDto::into(&result).is_err());
+    }
+
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_get_nodes_discards_unreadable() -> Result<(), String> {
+        let pool = redis_pool::new(REDIS_URL).await?;
+        let mut connection = pool.clone();
+        let memory = MemoryCache::new(0);
+        let config = config();
+        let cache = Cache::new(&pool, &memory, &config);
+
+        let c = node("integration_test_nodes", "1.0.0", &[]);
+        cache.save_nodes(&[&c]).await?;
+
+        let unreadable = ["1.0.1", "1.0.2", "1.0.3"]
+            .iter()
+            .map(|v| semver::Version::parse(v).unwrap())
+            .collect::<Vec<_>>();
+        let keys = [
+            Cache::get_node_key(&c.name, &unreadable[0]),
+            Cache::get_node_key(&c.name, &unreadable[1]),
+            Cache::get_node_key(&c.name, &unreadable[2]),
+        ];
+        write_unreadable(&mut connection, &keys).await?;
+
+        let mut name_versions = vec![(c.name.to_owned(), c.version.to_owned())];
+        name_versions.extend(unreadable.into_iter().map(|v| (c.name.to_owned(), v)));
+
+        let actual = cache.get_nodes(&name_versions).await?;
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual.get(&name_versions[0]), Some(&c));
+        assert_eq!(exists(&mut connection, &keys).await?, 0);
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_get_dependencies_discards_unreadable() -> Result<(), String> {
+        let pool = redis_pool::new(REDIS_URL).await?;
+        let mut connection = pool.clone();
+        let memory = MemoryCache::new(0);
+        let config = config();
+        let cache = Cache::new(&pool, &memory, &config);
+
+        let versions = ["1.0.1", "1.0.2", "1.0.3"]
+            .iter()
+            .map(|v| semver::Version::parse(v).unwrap())
+            .collect::<Vec<_>>();
+        let keys = [
+            Cache::get_dependencies_key("integration_test_dependencies", &versions[0]),
+            Cache::get_dependencies_key("integration_test_dependencies", &versions[1]),
+            Cache::get_dependencies_key("integration_test_dependencies", &versions[2]),
+        ];
+        write_unreadable(&mut connection, &keys).await?;
+
+        for version in &versions {
+            assert_eq!(
+                cache
+                    .get_dependencies("integration_test_dependencies", version)
+                    .await?,
+                None
+            );
+        }
+        assert_eq!(exists(&mut connection, &keys).await?, 0);
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_get_job_discards_unreadable() -> Result<(), String> {
+        let pool = redis_pool::new(REDIS_URL).await?;
+        let mut connection = pool.clone();
+        let memory = MemoryCache::new(0);
+        let config = config();
+        let cache = Cache::new(&pool, &memory, &config);
+
+        let ids = [
+            "integration_test_1",
+            "integration_test_2",
+            "integration_test_3",
+        ];
+        let keys = [
+            Cache::get_job_key(ids[0]),
+            Cache::get_job_key(ids[1]),
+            Cache::get_job_key(ids[2]),
+        ];
+        write_unreadable(&mut connection, &keys).await?;
+
+        for id in &ids {
+            assert!(cache.get_job(id).await?.is_none());
+        }
+        assert_eq!(exists(&mut connection, &keys).await?, 0);
+
+        Ok(())
+    }
 }
@@ -1,5 +1,13 @@
 use redis::Value;
 use std::str::from_utf8;
+use std::time::Duration;
+
+/// Keys examined by every `SCAN` iteration.
+const SCAN_COUNT: usize = 1000;
+
+/// Value of a key, `Err` when it cannot be read, with its remaining lifetime, `None` for keys
+/// without expiry.
+pub(crate) type Entry = (Result<String, String>, Option<Duration>);
 
 pub(crate) struct RedisCache<'a> {
     redis_pool: &'a redis::aio::MultiplexedConnection,
@@ -10,56 +18,282 @@ impl<'a> RedisCache<'a> {
         Self { redis_pool: pool }
     }
 
-    pub(crate) async fn get_string(&self, key: &str) -> Result<Option<String>, String> {
+    /// Value of the key, `Some(Err(..))` when the key holds something other than a utf8 string.
+    pub(crate) async fn get_string(
+        &self,
+        key: &str,
+    ) -> Result<Option<Result<String, String>>, String> {
         let fn_name = "get_string";
 
         let mut connection = self.redis_pool.clone();
 
-        let value: Value = redis::cmd("GET")
+        let value: Value = match redis::cmd("GET")
             .arg(&[key])
             .query_async(&mut connection)
             .await
-            .map_err(|error| Self::map_error(fn_name, &error))?;
+        {
+            Ok(value) => value,
+            Err(error) if error.code() == Some("WRONGTYPE") => {
+                return Ok(Some(Err(format!("{}: RedisError={:?}", fn_name, error))))
+            }
+            Err(error) => return Err(Self::map_error(fn_name, &error)),
+        };
 
         match value {
             Value::Nil => Ok(None),
-            Value::Int(_) => unimplemented!("int"),
-            Value::Data(data) => Ok(Some(
-                from_utf8(&data)
-                    .map_err(|error| format!("{:?}", error))?
-                    .to_owned(),
-            )),
-            Value::Bulk(_) => unimplemented!("bulk"),
-            Value::Status(_) => unimplemented!("status"),
-            Value::Okay => unimplemented!("okay"),
+            value => Ok(Some(Self::read_string(fn_name, &value))),
         }
     }
 
-    pub(crate) async fn set_string(&self, key: &str, value: &str) -> Result<(), String> {
+    /// Values of the keys, in order, with their remaining lifetime, `None` for missing keys.
+    /// Fetched with a single `MGET` and a `PTTL` per key in a transaction.
+    pub(crate) async fn get_strings_with_ttl(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<Option<Entry>>, String> {
+        let fn_name = "get_strings_with_ttl";
+
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut connection = self.redis_pool.clone();
+
+        let mut pipeline = redis::pipe();
+        pipeline.atomic().cmd("MGET").arg(keys);
+        for key in keys {
+            pipeline.cmd("PTTL").arg(key);
+        }
+
+        let results: Vec<Value> = pipeline
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))?;
+
+        Self::read_entries(fn_name, results)
+    }
+
+    /// Sets the values of the keys with a single pipeline, expiring each after `ttl` seconds
+    /// unless `ttl` is 0.
+    pub(crate) async fn set_strings(
+        &self,
+        entries: &[(String, String)],
+        ttl: u64,
+    ) -> Result<(), String> {
+        let fn_name = "set_strings";
+
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut connection = self.redis_pool.clone();
+
+        let mut pipeline = redis::pipe();
+        for (key, value) in entries {
+            pipeline.cmd("SET").arg(key).arg(value);
+            if ttl > 0 {
+                pipeline.arg("EX").arg(ttl);
+            }
+            pipeline.ignore();
+        }
+
+        pipeline
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))
+    }
+
+    /// Sets the value of the key, expiring it after `ttl` seconds unless `ttl` is 0.
+    pub(crate) async fn set_string(&self, key: &str, value: &str, ttl: u64) -> Result<(), String> {
         let fn_name = "set_string";
 
         let mut connection = self.redis_pool.clone();
 
-        let value: Value = redis::cmd("SET")
-            .arg(&[key, value])
+        let mut command = redis::cmd("SET");
+        command.arg(&[key, value]);
+        if ttl > 0 {
+            command.arg("EX").arg(ttl);
+        }
+
+        let value: Value = command
             .query_async(&mut connection)
             .await
             .map_err(|error| Self::map_error(fn_name, &error))?;
 
         match value {
-            Value::Nil => unimplemented!("Nil"),
-            Value::Int(_) => unimplemented!("Int"),
-            Value::Data(_) => unimplemented!("Data"),
-            Value::Bulk(_) => unimplemented!("Bulk"),
-            Value::Status(_) => unimplemented!("Status"),
             Value::Okay => Ok(()),
+            value => Err(Self::unexpected(fn_name, &value)),
+        }
+    }
+
+    /// Adds the member to every set, expiring each after `ttl` seconds unless `ttl` is 0.
+    pub(crate) async fn add_to_sets(
+        &self,
+        keys: &[String],
+        member: &str,
+        ttl: u64,
+    ) -> Result<(), String> {
+        let fn_name = "add_to_sets";
+
+        let mut connection = self.redis_pool.clone();
+
+        let mut pipeline = redis::pipe();
+        for key in keys {
+            pipeline.cmd("SADD").arg(key).arg(member).ignore();
+            if ttl > 0 {
+                pipeline.cmd("EXPIRE").arg(key).arg(ttl).ignore();
+            }
+        }
+
+        pipeline
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))
+    }
+
+    pub(crate) async fn members(&self, key: &str) -> Result<Vec<String>, String> {
+        let fn_name = "members";
+
+        let mut connection = self.redis_pool.clone();
+
+        redis::cmd("SMEMBERS")
+            .arg(&[key])
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))
+    }
+
+    /// Every key matching the glob style pattern, iterated with `SCAN` so Redis is not blocked.
+    pub(crate) async fn scan(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let fn_name = "scan";
+
+        let mut connection = self.redis_pool.clone();
+
+        let mut keys = Vec::new();
+        let mut cursor = 0u64;
+
+        loop {
+            let (next, mut batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_COUNT)
+                .query_async(&mut connection)
+                .await
+                .map_err(|error| Self::map_error(fn_name, &error))?;
+
+            keys.append(&mut batch);
+
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        // a key may be returned more than once while the keyspace is rehashed.
+        keys.sort();
+        keys.dedup();
+
+        Ok(keys)
+    }
+
+    /// Deletes the keys, returning how many existed.
+    pub(crate) async fn delete(&self, keys: &[String]) -> Result<usize, String> {
+        let fn_name = "delete";
+
+        if keys.is_empty() {
+            return Ok(0);
         }
+
+        let mut connection = self.redis_pool.clone();
+
+        redis::cmd("DEL")
+            .arg(keys)
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))
+    }
+
+    /// Publishes the message to the subscribers of the channel.
+    pub(crate) async fn publish(&self, channel: &str, message: &str) -> Result<(), String> {
+        let fn_name = "publish";
+
+        let mut connection = self.redis_pool.clone();
+
+        let _: Value = redis::cmd("PUBLISH")
+            .arg(channel)
+            .arg(message)
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn increment(&self, key: &str) -> Result<i64, String> {
+        let fn_name = "increment";
+
+        let mut connection = self.redis_pool.clone();
+
+        redis::cmd("INCR")
+            .arg(&[key])
+            .query_async(&mut connection)
+            .await
+            .map_err(|error| Self::map_error(fn_name, &error))
     }
 
     fn map_error(fn_name: &str, error: &redis::RedisError) -> String {
         log::error!("{}: RedisError={:?}", fn_name, error);
         format!("{}: RedisError={:?}", fn_name, error)
     }
+
+    /// Reads the `MGET` reply and the `PTTL` replies that follow it, an entry that cannot be read
+    /// failing on its own rather than the whole batch.
+    fn read_entries(fn_name: &str, results: Vec<Value>) -> Result<Vec<Option<Entry>>, String> {
+        let mut results = results.into_iter();
+        let values = match results.next() {
+            Some(Value::Bulk(values)) => values,
+            Some(value) => return Err(Self::unexpected(fn_name, &value)),
+            None => Vec::new(),
+        };
+        let ttls = results;
+
+        values
+            .into_iter()
+            .zip(ttls)
+            .map(|(value, ttl)| {
+                let ttl: i64 = redis::from_redis_value(&ttl)
+                    .map_err(|error| Self::map_error(fn_name, &error))?;
+
+                // -2 is a missing key, -1 a key without expiry. `MGET` replies nil for keys of
+                // another type, which still have a lifetime.
+                let ttl = match (&value, ttl) {
+                    (Value::Nil, -2) => return Ok(None),
+                    (_, -1) => None,
+                    (_, ttl) => Some(Duration::from_millis(ttl.max(0) as u64)),
+                };
+
+                Ok(Some((Self::read_string(fn_name, &value), ttl)))
+            })
+            .collect()
+    }
+
+    /// Reads the value of a key that exists, `Err` when it is not a utf8 string.
+    fn read_string(fn_name: &str, value: &Value) -> Result<String, String> {
+        match value {
+            Value::Data(data) => from_utf8(data)
+                .map(|value| value.to_owned())
+                .map_err(|error| format!("{}: Error={:?}", fn_name, error)),
+            Value::Nil => Err(format!("{}: key holds another type", fn_name)),
+            value => Err(format!("{}: unexpected value={:?}", fn_name, value)),
+        }
+    }
+
+    fn unexpected(fn_name: &str, value: &Value) -> String {
+        log::error!("{}: unexpected value={:?}", fn_name, value);
+        format!("{}: unexpected value={:?}", fn_name, value)
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +304,37 @@ mod tests {
 
     const REDIS_URL: &str = "redis://localhost:6379";
 
+    #[test]
+    fn read_entries_one_by_one() {
+        let results = vec![
+            Value::Bulk(vec![
+                Value::Data(b"a".to_vec()),
+                Value::Data(vec![0xff, 0xfe]),
+                Value::Nil,
+                Value::Nil,
+                Value::Data(b"b".to_vec()),
+            ]),
+            Value::Int(-1),
+            Value::Int(1000),
+            Value::Int(-2),
+            Value::Int(2000),
+            Value::Int(3000),
+        ];
+
+        let actual = RedisCache::read_entries("test", results).unwrap();
+
+        assert_eq!(actual.len(), 5);
+        assert_eq!(actual[0], Some((Ok("a".to_owned()), None)));
+        assert!(matches!(&actual[1], Some((Err(_), Some(ttl))) if ttl.as_millis() == 1000));
+        assert_eq!(actual[2], None);
+        // a key of another type.
+        assert!(matches!(&actual[3], Some((Err(_), Some(ttl))) if ttl.as_millis() == 2000));
+        assert_eq!(
+            actual[4],
+            Some((Ok("b".to_owned()), Some(Duration::from_millis(3000))))
+        );
+    }
+
     #[actix_rt::test]
     #[ignore]
     async fn integration_get_string_some() -> Result<(), String> {
@@ -83,12 +348,33 @@ mod tests {
 
         assert_eq!(
             cache.get_string("integration_test:get_string:some").await?,
-            Some("value".to_owned())
+            Some(Ok("value".to_owned()))
         );
 
         Ok(())
     }
 
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_get_string_wrong_type() -> Result<(), String> {
+        let mut pool = redis_pool::new(REDIS_URL).await?;
+        let _: () = pool
+            .sadd("integration_test:get_string:wrong_type", "value")
+            .await
+            .map_err(|error| RedisCache::map_error("integration_get_string_wrong_type", &error))?;
+
+        let cache = RedisCache::new(&pool);
+
+        assert!(matches!(
+            cache
+                .get_string("integration_test:get_string:wrong_type")
+                .await?,
+            Some(Err(_))
+        ));
+
+        Ok(())
+    }
+
     #[actix_rt::test]
     #[ignore]
     async fn integration_get_string_none() -> Result<(), String> {
@@ -103,6 +389,20 @@ mod tests {
         Ok(())
     }
 
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_increment() -> Result<(), String> {
+        let pool = redis_pool::new(REDIS_URL).await?;
+        let cache = RedisCache::new(&pool);
+
+        let first = cache.increment("integration_test:increment").await?;
+        let second = cache.increment("integration_test:increment").await?;
+
+        assert_eq!(second, first + 1);
+
+        Ok(())
+    }
+
     #[actix_rt::test]
     #[ignore]
     async fn integration_set_string() -> Result<(), String> {
@@ -111,11 +411,98 @@ mod tests {
 
         assert_eq!(
             cache
-                .set_string("integration_test:set_string", "value")
+                .set_string("integration_test:set_string", "value", 0)
                 .await?,
             ()
         );
 
         Ok(())
     }
+
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_set_string_ttl() -> Result<(), String> {
+        let mut pool = redis_pool::new(REDIS_URL).await?;
+        let cache = RedisCache::new(&pool);
+
+        cache
+            .set_string("integration_test:set_string_ttl", "value", 60)
+            .await?;
+
+        let ttl: i64 = pool
+            .ttl("integration_test:set_string_ttl")
+            .await
+            .map_err(|error| RedisCache::map_error("integration_set_string_ttl", &error))?;
+
+        assert!(ttl > 0 && ttl <= 60);
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_set_strings_get_strings_with_ttl() -> Result<(), String> {
+        let pool = redis_pool::new(REDIS_URL).await?;
+        let cache = RedisCache::new(&pool);
+
+        cache
+            .set_strings(
+                &[("integration_test:strings:ttl".to_owned(), "1".to_owned())],
+                60,
+            )
+            .await?;
+        cache
+            .set_strings(
+                &[("integration_test:strings:none".to_owned(), "2".to_owned())],
+                0,
+            )
+            .await?;
+
+        let actual = cache
+            .get_strings_with_ttl(&[
+                "integration_test:strings:ttl".to_owned(),
+                "integration_test:strings:missing".to_owned(),
+                "integration_test:strings:none".to_owned(),
+            ])
+            .await?;
+
+        assert!(
+            matches!(&actual[0], Some((Ok(value), Some(ttl))) if value == "1" && ttl.as_secs() <= 60)
+        );
+        assert_eq!(actual[1], None);
+        assert_eq!(actual[2], Some((Ok("2".to_owned()), None)));
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[ignore]
+    async fn integration_scan_delete() -> Result<(), String> {
+        let pool = redis_pool::new(REDIS_URL).await?;
+        let cache = RedisCache::new(&pool);
+
+        cache
+            .set_string("integration_test:scan:1", "value", 0)
+            .await?;
+        cache
+            .set_string("integration_test:scan:2", "value", 0)
+            .await?;
+
+        let keys = cache.scan("integration_test:scan:*").await?;
+
+        assert_eq!(
+            keys,
+            vec![
+                "integration_test:scan:1".to_owned(),
+                "integration_test:scan:2".to_owned()
+            ]
+        );
+        assert_eq!(cache.delete(&keys).await?, 2);
+        assert_eq!(
+            cache.scan("integration_test:scan:*").await?,
+            Vec::<String>::new()
+        );
+
+        Ok(())
+    }
 }
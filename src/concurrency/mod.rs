@@ -0,0 +1,162 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Semaphore;
+
+/// Bounds the upstream fan-out of a single resolution and of all resolutions across requests.
+pub(crate) struct Limiter {
+    resolution: usize,
+    global: usize,
+    semaphore: Semaphore,
+    waiting: AtomicUsize,
+    running: AtomicUsize,
+}
+
+impl Limiter {
+    pub(crate) fn new(resolution: usize, global: usize) -> Self {
+        Self {
+            resolution: resolution.max(1),
+            global: global.max(1),
+            semaphore: Semaphore::new(global.max(1)),
+            waiting: AtomicUsize::new(0),
+            running: AtomicUsize::new(0),
+        }
+    }
+
+    /// Maximum number of concurrent tasks within a single resolution.
+    pub(crate) fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    /// Maximum number of concurrent tasks across all resolutions.
+    pub(crate) fn global(&self) -> usize {
+        self.global
+    }
+
+    /// Number of tasks queued for a global permit.
+    pub(crate) fn waiting(&self) -> usize {
+        self.waiting.load(Ordering::Relaxed)
+    }
+
+    /// Number of tasks holding a global permit.
+    pub(crate) fn running(&self) -> usize {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Runs `future` once a global permit is available.
+    pub(crate) async fn run<F: Future>(&self, future: F) -> F::Output {
+        let waiting = Gauge::new(&self.waiting);
+        let _permit = self.semaphore.acquire().await;
+        drop(waiting);
+
+        let _running = Gauge::new(&self.running);
+        future.await
+    }
+
+    /// A limiter for a single resolution, bounded by both the resolution and the global limit.
+    pub(crate) fn resolution_limiter(&self) -> ResolutionLimiter<'_> {
+        ResolutionLimiter {
+            limiter: self,
+            semaphore: Semaphore::new(self.resolution),
+        }
+    }
+}
+
+/// Bounds the upstream requests of a single resolution, whichever level of the traversal they are
+/// made from.
+pub(crate) struct ResolutionLimiter<'a> {
+    limiter: &'a Limiter,
+    semaphore: Semaphore,
+}
+
+impl<'a> ResolutionLimiter<'a> {
+    /// Runs `future` once a resolution permit, then a global permit, is available.
+    pub(crate) async fn run<F: Future>(&self, future: F) -> F::Output {
+        let _permit = self.semaphore.acquire().await;
+        self.limiter.run(future).await
+    }
+}
+
+/// Counts a task for as long as it is alive, including when its future is dropped early.
+struct Gauge<'a>(&'a AtomicUsize);
+
+impl<'a> Gauge<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl<'a> Drop for Gauge<'a> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Poll;
+
+    #[actix_rt::test]
+    async fn run_counts_tasks() {
+        let limiter = Limiter::new(2, 1);
+
+        let output = limiter
+            .run(async {
+                assert_eq!(limiter.waiting(), 0);
+                assert_eq!(limiter.running(), 1);
+                "output"
+            })
+            .await;
+
+        assert_eq!(output, "output");
+        assert_eq!(limiter.waiting(), 0);
+        assert_eq!(limiter.running(), 0);
+    }
+
+    #[actix_rt::test]
+    async fn resolution_limiter_bounds_resolution() {
+        let limiter = Limiter::new(1, 2);
+        let resolution_limiter = limiter.resolution_limiter();
+
+        let running = futures::future::join(
+            resolution_limiter.run(async {
+                yield_once().await;
+                limiter.running()
+            }),
+            resolution_limiter.run(async {
+                yield_once().await;
+                limiter.running()
+            }),
+        )
+        .await;
+
+        // the global limit allows two, but the resolution only one at a time.
+        assert_eq!(running, (1, 1));
+        assert_eq!(limiter.running(), 0);
+    }
+
+    /// Lets every other task run before completing.
+    async fn yield_once() {
+        let mut yielded = false;
+
+        futures::future::poll_fn(|context| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    #[test]
+    fn new_at_least_one() {
+        let limiter = Limiter::new(0, 0);
+
+        assert_eq!(limiter.resolution(), 1);
+        assert_eq!(limiter.global(), 1);
+    }
+}
@@ -1,8 +1,24 @@
+const DEFAULT_RESOLUTION_CONCURRENCY: usize = 16;
+const DEFAULT_GLOBAL_CONCURRENCY: usize = 64;
+const DEFAULT_DEPENDENCIES_TTL: u64 = 24 * 60 * 60;
+const DEFAULT_NODES_TTL: u64 = 24 * 60 * 60;
+const DEFAULT_JOBS_TTL: u64 = 24 * 60 * 60;
+const DEFAULT_MEMORY_CAPACITY: usize = 100_000;
+
 #[derive(Debug)]
 pub struct Config {
     pub(crate) mysql_url: String,
     pub(crate) redis_url: String,
     pub(crate) server_address: String,
+    pub(crate) resolution_concurrency: usize,
+    pub(crate) global_concurrency: usize,
+    pub(crate) dependencies_enabled: bool,
+    pub(crate) dependencies_ttl: u64,
+    pub(crate) nodes_ttl: u64,
+    pub(crate) jobs_ttl: u64,
+    pub(crate) memory_enabled: bool,
+    pub(crate) memory_capacity: usize,
+    pub(crate) admin_token: Option<String>,
 }
 
 impl Config {
@@ -16,12 +32,75 @@ impl Config {
             mysql_url: mysql_url.to_owned(),
             redis_url: redis_url.to_owned(),
             server_address: format!("{}:{}", server_address_host, server_address_port),
+            resolution_concurrency: DEFAULT_RESOLUTION_CONCURRENCY,
+            global_concurrency: DEFAULT_GLOBAL_CONCURRENCY,
+            dependencies_enabled: true,
+            dependencies_ttl: DEFAULT_DEPENDENCIES_TTL,
+            nodes_ttl: DEFAULT_NODES_TTL,
+            jobs_ttl: DEFAULT_JOBS_TTL,
+            memory_enabled: true,
+            memory_capacity: DEFAULT_MEMORY_CAPACITY,
+            admin_token: None,
         };
 
         log::debug!("{:?}", config);
 
         config
     }
+
+    /// Maximum number of concurrent upstream lookups within a single resolution.
+    pub fn with_resolution_concurrency(mut self, resolution_concurrency: usize) -> Self {
+        self.resolution_concurrency = resolution_concurrency;
+        self
+    }
+
+    /// Maximum number of concurrent upstream lookups across all resolutions.
+    pub fn with_global_concurrency(mut self, global_concurrency: usize) -> Self {
+        self.global_concurrency = global_concurrency;
+        self
+    }
+
+    /// Whether whole dependency graphs are cached, on top of the crates they are assembled from.
+    pub fn with_dependencies_enabled(mut self, dependencies_enabled: bool) -> Self {
+        self.dependencies_enabled = dependencies_enabled;
+        self
+    }
+
+    /// Seconds a cached dependency graph is kept, 0 keeping it until evicted.
+    pub fn with_dependencies_ttl(mut self, dependencies_ttl: u64) -> Self {
+        self.dependencies_ttl = dependencies_ttl;
+        self
+    }
+
+    /// Seconds a cached crate with its direct dependencies is kept, 0 keeping it until evicted.
+    pub fn with_nodes_ttl(mut self, nodes_ttl: u64) -> Self {
+        self.nodes_ttl = nodes_ttl;
+        self
+    }
+
+    /// Seconds a job is kept, 0 keeping it until evicted.
+    pub fn with_jobs_ttl(mut self, jobs_ttl: u64) -> Self {
+        self.jobs_ttl = jobs_ttl;
+        self
+    }
+
+    /// Whether crates and graphs are also cached in process, in front of Redis.
+    pub fn with_memory_enabled(mut self, memory_enabled: bool) -> Self {
+        self.memory_enabled = memory_enabled;
+        self
+    }
+
+    /// Maximum number of crates cached in process, a graph counting as many crates as it holds.
+    pub fn with_memory_capacity(mut self, memory_capacity: usize) -> Self {
+        self.memory_capacity = memory_capacity;
+        self
+    }
+
+    /// Bearer token required by the endpoints meant for operators, disabled without one.
+    pub fn with_admin_token(mut self, admin_token: &str) -> Self {
+        self.admin_token = Some(admin_token.to_owned());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -43,5 +122,68 @@ mod tests {
             config.server_address,
             "my server address host:my server address port"
         );
+        assert_eq!(
+            config.resolution_concurrency,
+            DEFAULT_RESOLUTION_CONCURRENCY
+        );
+        assert_eq!(config.global_concurrency, DEFAULT_GLOBAL_CONCURRENCY);
+        assert!(config.dependencies_enabled);
+        assert_eq!(config.dependencies_ttl, DEFAULT_DEPENDENCIES_TTL);
+        assert_eq!(config.nodes_ttl, DEFAULT_NODES_TTL);
+        assert_eq!(config.jobs_ttl, DEFAULT_JOBS_TTL);
+        assert!(config.memory_enabled);
+        assert_eq!(config.memory_capacity, DEFAULT_MEMORY_CAPACITY);
+        assert!(config.admin_token.is_none());
+    }
+
+    #[test]
+    fn with_concurrency() {
+        let config = Config::new(
+            "my mysql url",
+            "my redis url",
+            "my server address host",
+            "my server address port",
+        )
+        .with_resolution_concurrency(4)
+        .with_global_concurrency(8);
+
+        assert_eq!(config.resolution_concurrency, 4);
+        assert_eq!(config.global_concurrency, 8);
+    }
+
+    #[test]
+    fn with_cache() {
+        let config = Config::new(
+            "my mysql url",
+            "my redis url",
+            "my server address host",
+            "my server address port",
+        )
+        .with_dependencies_enabled(false)
+        .with_dependencies_ttl(60)
+        .with_nodes_ttl(120)
+        .with_jobs_ttl(0)
+        .with_memory_enabled(false)
+        .with_memory_capacity(10);
+
+        assert!(!config.dependencies_enabled);
+        assert_eq!(config.dependencies_ttl, 60);
+        assert_eq!(config.nodes_ttl, 120);
+        assert_eq!(config.jobs_ttl, 0);
+        assert!(!config.memory_enabled);
+        assert_eq!(config.memory_capacity, 10);
+    }
+
+    #[test]
+    fn with_admin_token() {
+        let config = Config::new(
+            "my mysql url",
+            "my redis url",
+            "my server address host",
+            "my server address port",
+        )
+        .with_admin_token("my admin token");
+
+        assert_eq!(config.admin_token.as_deref(), Some("my admin token"));
     }
 }
@@ -0,0 +1,22 @@
+use crate::domain::Crate;
+
+/// Progress of a dependency graph resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ResolutionEvent {
+    /// A breadth first search layer is being resolved.
+    Layer { depth: usize, crates: usize },
+    /// A crate has been resolved.
+    Node(Crate, Source),
+    /// The resolution failed.
+    Error(String),
+    /// The resolution finished with the complete graph.
+    Complete(Vec<Crate>),
+}
+
+/// Where a crate was resolved from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Source {
+    Cache,
+    Database,
+    Upstream,
+}
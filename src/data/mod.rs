@@ -1,14 +1,27 @@
+mod events;
+
 use crate::api::Api;
-use crate::cache::Cache;
-use crate::domain::Crate;
+use crate::cache::{Cache, CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
+use crate::domain::{Crate, CrateMetadata};
+use crate::graph;
+use crate::graph::Rules;
 use crate::persistence::Persistence;
+use futures::channel::mpsc::UnboundedSender;
+use futures::StreamExt;
 use semver::Version;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+pub(crate) use events::{ResolutionEvent, Source};
 
 pub(crate) struct Data<'a> {
     api: Api<'a>,
     cache: Cache<'a>,
     persistence: Persistence<'a>,
+    limiter: &'a Limiter,
+    events: Option<UnboundedSender<ResolutionEvent>>,
+    complete_event: bool,
+    rules: Rules,
 }
 
 impl<'a> Data<'a> {
@@ -16,106 +29,456 @@ impl<'a> Data<'a> {
         database_pool: &'a sqlx::MySqlPool,
         http_client_pool: &'a reqwest::Client,
         redis_pool: &'a redis::aio::MultiplexedConnection,
+        limiter: &'a Limiter,
+        memory_cache: &'a MemoryCache,
+        cache_config: &'a CacheConfig,
     ) -> Self {
         Self {
-            api: Api::new(http_client_pool),
-            cache: Cache::new(redis_pool),
+            api: Api::new(http_client_pool, limiter),
+            cache: Cache::new(redis_pool, memory_cache, cache_config),
             persistence: Persistence::new(database_pool),
+            limiter,
+            events: None,
+            complete_event: true,
+            rules: Rules::default(),
         }
     }
 
+    /// Reports the progress of every resolution to `events`.
+    pub(crate) fn with_events(mut self, events: UnboundedSender<ResolutionEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Leaves the resolved graph out of the events, for consumers only following crates.
+    pub(crate) fn without_complete_event(mut self) -> Self {
+        self.complete_event = false;
+        self
+    }
+
+    /// Restricts every resolution to the crates allowed by `rules`.
+    pub(crate) fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
     pub(crate) async fn get_dependency_graph(
         &self,
         name: String,
         version: Version,
+        depth: Option<usize>,
     ) -> Result<Vec<Crate>, String> {
-        let fn_name = "get_dependency_graph";
+        let result = self.resolve(name, version, depth).await;
+
+        match &result {
+            Ok(crates) if self.complete_event => {
+                self.emit(|| ResolutionEvent::Complete(crates.clone()))
+            }
+            Ok(_) => {}
+            Err(e) => self.emit(|| ResolutionEvent::Error(e.clone())),
+        }
+
+        result
+    }
+
+    /// Resolves the dependency graphs of many roots with a single traversal, so crates shared
+    /// between roots are looked up once. Returns the graph of every root, in order.
+    pub(crate) async fn get_dependency_graphs(
+        &self,
+        roots: Vec<(String, Version)>,
+    ) -> Result<Vec<Vec<Crate>>, String> {
+        let result = self.resolve_batch(roots).await;
+
+        match &result {
+            Ok(graphs) if self.complete_event => {
+                self.emit(|| ResolutionEvent::Complete(graph::merge(graphs)))
+            }
+            Ok(_) => {}
+            Err(e) => self.emit(|| ResolutionEvent::Error(e.clone())),
+        }
+
+        result
+    }
+
+    async fn resolve_batch(
+        &self,
+        roots: Vec<(String, Version)>,
+    ) -> Result<Vec<Vec<Crate>>, String> {
+        let cached = futures::future::try_join_all(
+            roots
+                .iter()
+                .map(|(name, version)| self.cache.get_dependencies(name, version)),
+        )
+        .await?;
+
+        let mut hash: HashMap<(String, Version), Crate> = HashMap::new();
+        let mut missing = Vec::new();
+
+        for (root, cached) in roots.iter().zip(cached) {
+            match cached {
+                Some(crates) => {
+                    for c in crates {
+                        self.emit(|| ResolutionEvent::Node(c.clone(), Source::Cache));
+                        hash.insert((c.name.to_owned(), c.version.to_owned()), c);
+                    }
+                }
+                None => missing.push(root.to_owned()),
+            }
+        }
+
+        self.traverse(missing.clone(), &mut hash, None).await?;
+
+        let mut crates = hash.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+
+        crates.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+        let mut results = Vec::new();
 
+        for (name, version) in &roots {
+            let results_for_root = if self.rules.is_empty() {
+                graph::reachable(name, version, &crates)
+            } else {
+                graph::restrict(name, version, &crates, &self.rules)
+            };
+
+            // only unrestricted graphs are cached.
+            if self.rules.is_empty() && missing.contains(&(name.to_owned(), version.to_owned())) {
+                self.cache
+                    .save_dependencies(name, version, &results_for_root)
+                    .await?;
+            }
+
+            results.push(results_for_root);
+        }
+
+        Ok(results)
+    }
+
+    async fn resolve(
+        &self,
+        name: String,
+        version: Version,
+        depth: Option<usize>,
+    ) -> Result<Vec<Crate>, String> {
         if let Some(results) = self.cache.get_dependencies(&name, &version).await? {
+            let results = if self.rules.is_empty() {
+                results
+            } else {
+                graph::restrict(&name, &version, &results, &self.rules)
+            };
+
+            let results = match depth {
+                Some(depth) => graph::limit_depth(&name, &version, &results, depth),
+                None => results,
+            };
+
+            if self.events.is_some() {
+                // replayed layer by layer, as if traversed.
+                let annotations = graph::annotate(&name, &version, &results);
+                let mut layers: BTreeMap<usize, Vec<&Crate>> = BTreeMap::new();
+                for c in &results {
+                    let depth = annotations
+                        .get(&(c.name.to_owned(), c.version.to_owned()))
+                        .map(|a| a.depth)
+                        .unwrap_or_default();
+                    layers.entry(depth).or_default().push(c);
+                }
+
+                for (depth, crates) in layers {
+                    self.emit(|| ResolutionEvent::Layer {
+                        depth,
+                        crates: crates.len(),
+                    });
+                    for c in crates {
+                        self.emit(|| ResolutionEvent::Node(c.clone(), Source::Cache));
+                    }
+                }
+            }
+
             return Ok(results);
         }
 
         let mut hash: HashMap<(String, Version), Crate> = HashMap::new();
-        let mut stack: Vec<(String, Version)> = Vec::new();
-        stack.push((name.to_owned(), version.to_owned()));
+        self.traverse(
+            vec![(name.to_owned(), version.to_owned())],
+            &mut hash,
+            depth,
+        )
+        .await?;
+
+        let mut results = hash.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+
+        results.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+        if !self.rules.is_empty() {
+            results = graph::restrict(&name, &version, &results, &self.rules);
+        }
+
+        // only complete, unrestricted graphs are cached.
+        if depth.is_none() && self.rules.is_empty() {
+            self.cache
+                .save_dependencies(&name, &version, &results)
+                .await?;
+        }
+
+        Ok(results)
+    }
+
+    /// Breadth first search from the roots, adding every crate not already in `hash`. Excluded
+    /// crates are not visited and pruned crates are not expanded.
+    async fn traverse(
+        &self,
+        roots: Vec<(String, Version)>,
+        hash: &mut HashMap<(String, Version), Crate>,
+        depth: Option<usize>,
+    ) -> Result<(), String> {
+        let mut stack = roots;
+        let mut layer = 0;
 
         while !&stack.is_empty() {
-            let name_versions = stack
+            let mut name_versions = stack
                 .iter()
                 .filter(|&name_version| !hash.contains_key(name_version))
                 .map(|(name, version)| (name.to_owned(), version.to_owned()))
                 .collect::<Vec<_>>();
+            name_versions.sort();
+            name_versions.dedup();
 
             stack.clear();
 
             if !name_versions.is_empty() {
-                let mut results = self.persistence.get_one_batch(&name_versions).await?;
-                log::info!("{}: database_create={:?}", fn_name, results);
-
-                let missing_name_versions = results
-                    .iter()
-                    .filter_map(|((n, v), c)| {
-                        if c.is_none() {
-                            Some((n.to_owned(), v.to_owned()))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>();
-
-                let api_crates = futures::future::join_all(missing_name_versions.iter().map(
-                    |(name, version)| {
-                        let name = name.to_owned();
-                        let version = version.to_owned();
-                        async move {
-                            let c = self.api.get_crate(&name, &version).await?;
-
-                            self.persistence.save_one(&c).await?;
-
-                            Ok::<Crate, String>(c)
-                        }
-                    },
-                ))
-                .await;
-
-                for api_crate_result in api_crates {
-                    if let Err(e) = api_crate_result {
-                        return Err(e);
-                    }
+                self.emit(|| ResolutionEvent::Layer {
+                    depth: layer,
+                    crates: name_versions.len(),
+                });
 
-                    let api_crate = api_crate_result.unwrap();
-
-                    results.insert(
-                        (api_crate.name.to_owned(), api_crate.version.to_owned()),
-                        Some(api_crate.clone()),
-                    );
-                }
+                let results = self.get_crates(&name_versions).await?;
 
                 stack = results
-                    .iter()
-                    .map(|(_, c)| c.clone().unwrap())
-                    .map(|c| c.dependency)
-                    .flatten()
-                    .map(|d| (d.name, d.version))
+                    .values()
+                    .filter(|c| !self.rules.prunes(&c.name))
+                    .flat_map(|c| c.dependency.iter())
+                    .filter(|d| !self.rules.excludes(&d.name))
+                    .map(|d| (d.name.to_owned(), d.version.to_owned()))
                     .collect();
 
-                for ((n, v), c) in results {
-                    let mut c = c.unwrap();
-                    c.dependency
-                        .sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
-                    hash.insert((n, v), c);
+                hash.extend(results);
+            }
+
+            // breadth first, so every crate in the layer is at its minimum depth.
+            if matches!(depth, Some(depth) if layer >= depth) {
+                stack.clear();
+            }
+            layer += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Looks crates up in the cache then the database, resolving and saving those missing from
+    /// upstream.
+    pub(crate) async fn get_crates(
+        &self,
+        name_versions: &[(String, Version)],
+    ) -> Result<HashMap<(String, Version), Crate>, String> {
+        let fn_name = "get_crates";
+
+        let mut crates = self.cache.get_nodes(name_versions).await?;
+
+        for c in crates.values() {
+            self.emit(|| ResolutionEvent::Node(c.clone(), Source::Cache));
+        }
+
+        let uncached_name_versions = name_versions
+            .iter()
+            .filter(|&name_version| !crates.contains_key(name_version))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if uncached_name_versions.is_empty() {
+            return Ok(crates);
+        }
+
+        let results = self
+            .persistence
+            .get_one_batch(&uncached_name_versions)
+            .await?;
+        log::info!("{}: database_create={:?}", fn_name, results);
+
+        let mut uncached = HashMap::new();
+        let mut missing_name_versions = Vec::new();
+
+        for (name_version, c) in results {
+            match c {
+                Some(c) => {
+                    self.emit(|| ResolutionEvent::Node(c.clone(), Source::Database));
+                    uncached.insert(name_version, c);
                 }
+                None => missing_name_versions.push(name_version),
             }
         }
 
-        let mut results = hash.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+        let api_crates =
+            futures::stream::iter(missing_name_versions.iter().map(|(name, version)| {
+                let name = name.to_owned();
+                let version = version.to_owned();
+                // the api takes a permit for each of its upstream requests.
+                async move {
+                    let c = self.api.get_crate(&name, &version).await?;
 
-        results.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+                    self.persistence.save_one(&c).await?;
+
+                    Ok::<Crate, String>(c)
+                }
+            }))
+            .buffer_unordered(self.limiter.resolution())
+            .collect::<Vec<_>>()
+            .await;
+
+        for api_crate_result in api_crates {
+            let api_crate = api_crate_result?;
+
+            self.emit(|| ResolutionEvent::Node(api_crate.clone(), Source::Upstream));
+
+            uncached.insert(
+                (api_crate.name.to_owned(), api_crate.version.to_owned()),
+                api_crate,
+            );
+        }
+
+        for c in uncached.values_mut() {
+            c.dependency
+                .sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        }
 
         self.cache
-            .save_dependencies(&name, &version, &results)
+            .save_nodes(&uncached.values().collect::<Vec<_>>())
             .await?;
 
+        crates.extend(uncached);
+
+        Ok(crates)
+    }
+
+    /// Resolves a crate from upstream again, replacing the saved one, for when it was saved
+    /// with outdated dependencies. Returns the number of cache entries invalidated.
+    pub(crate) async fn refresh_crate(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> Result<usize, String> {
+        let c = self.api.get_crate(name, version).await?;
+
+        self.persistence.replace_one(&c).await?;
+
+        // graphs cached before were assembled from the dependencies replaced.
+        self.cache.invalidate(&c.name, &c.version).await
+    }
+
+    /// Every published version of the crate, oldest first.
+    pub(crate) async fn get_versions(&self, name: &str) -> Result<Vec<Version>, String> {
+        let mut versions = self.api.get_versions(name).await?;
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Fills in the metadata of the crates missing it, as only SBOMs need it. Crates without a
+    /// checksum are taken to be missing it, crates.io publishing one for every crate.
+    pub(crate) async fn fill_metadata(&self, crates: &mut [Crate]) -> Result<(), String> {
+        let name_versions = crates
+            .iter()
+            .filter(|c| c.metadata.checksum.is_none())
+            .map(|c| (c.name.to_owned(), c.version.to_owned()))
+            .collect::<Vec<_>>();
+
+        let mut metadata = self.get_metadata(&name_versions).await?;
+
+        for c in crates.iter_mut() {
+            if let Some(m) = metadata.remove(&(c.name.to_owned(), c.version.to_owned())) {
+                c.metadata = m;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Looks the metadata of crates up in the database, then upstream for the crates recorded
+    /// without it, saving what is found. Metadata failing to be looked up upstream is missing.
+    pub(crate) async fn get_metadata(
+        &self,
+        name_versions: &[(String, Version)],
+    ) -> Result<HashMap<(String, Version), CrateMetadata>, String> {
+        let fn_name = "get_metadata";
+
+        if name_versions.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut results = HashMap::new();
+        let mut missing_name_versions = Vec::new();
+
+        for (name_version, c) in self.persistence.get_one_batch(name_versions).await? {
+            match c {
+                Some(c) if c.metadata.checksum.is_some() => {
+                    results.insert(name_version, c.metadata);
+                }
+                _ => missing_name_versions.push(name_version),
+            }
+        }
+
+        let api_metadata = futures::stream::iter(missing_name_versions.into_iter().map(
+            |name_version| async move {
+                let result = self
+                    .api
+                    .get_metadata(&name_version.0, &name_version.1)
+                    .await;
+                (name_version, result)
+            },
+        ))
+        .buffer_unordered(self.limiter.resolution())
+        .collect::<Vec<_>>()
+        .await;
+
+        for ((name, version), result) in api_metadata {
+            let metadata = match result {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    log::warn!(
+                        "{}: metadata missing: name={:?} version={:?} error={}",
+                        fn_name,
+                        name,
+                        version,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            // backfills crates recorded before metadata was collected.
+            if let Err(e) = self
+                .persistence
+                .save_metadata(&name, &version, &metadata)
+                .await
+            {
+                log::warn!(
+                    "{}: metadata not saved: name={:?} version={:?} error={}",
+                    fn_name,
+                    name,
+                    version,
+                    e
+                );
+            }
+
+            results.insert((name, version), metadata);
+        }
+
         Ok(results)
     }
+
+    fn emit<F: FnOnce() -> ResolutionEvent>(&self, event: F) {
+        if let Some(events) = &self.events {
+            // the receiver going away must not fail the resolution.
+            let _ = events.unbounded_send(event());
+        }
+    }
 }
@@ -5,10 +5,79 @@ pub(crate) struct Crate {
     pub(crate) name: String,
     pub(crate) version: Version,
     pub(crate) dependency: Vec<CrateDependency>,
+    pub(crate) metadata: CrateMetadata,
+}
+
+/// Published metadata of a crate, only looked up when needed, so absent until then.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct CrateMetadata {
+    /// SPDX license expression.
+    pub(crate) license: Option<String>,
+    /// SHA-256 of the `.crate` file, hex encoded.
+    pub(crate) checksum: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct CrateDependency {
     pub(crate) name: String,
     pub(crate) version: Version,
+    /// Version requirement as declared, absent for dependencies recorded before it was collected.
+    pub(crate) req: Option<String>,
+    pub(crate) kind: DependencyKind,
+    pub(crate) optional: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DependencyKind {
+    Normal,
+    Build,
+    Dev,
+}
+
+impl DependencyKind {
+    /// Parses the kind as named by crates.io.
+    pub(crate) fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "normal" => Some(DependencyKind::Normal),
+            "build" => Some(DependencyKind::Build),
+            "dev" => Some(DependencyKind::Dev),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Build => "build",
+            DependencyKind::Dev => "dev",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Job {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) version: Version,
+    pub(crate) depth: Option<usize>,
+    pub(crate) status: JobStatus,
+    pub(crate) progress: JobProgress,
+    pub(crate) result: Option<Vec<Crate>>,
+    pub(crate) error: Option<String>,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct JobProgress {
+    pub(crate) depth: usize,
+    pub(crate) crates: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
 }
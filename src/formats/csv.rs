@@ -0,0 +1,65 @@
+use crate::domain::Crate;
+use std::fmt::Write;
+
+pub(crate) const HEADER: &str = "parent,parent_version,child,child_version,req,kind\n";
+
+/// Renders the dependencies of a crate as CSV rows, one dependency per row, following `HEADER`.
+pub(crate) fn rows(c: &Crate) -> String {
+    let mut csv = String::new();
+
+    for d in &c.dependency {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            field(&c.name),
+            c.version,
+            field(&d.name),
+            d.version,
+            field(d.req.as_deref().unwrap_or_default()),
+            d.kind.as_str()
+        )
+        .unwrap();
+    }
+
+    csv
+}
+
+/// Quotes a field holding a delimiter, a quote or a line break.
+fn field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn rows_csv() {
+        let mut input = node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]);
+        input.dependency[0].req = Some(">=1.0, <2.0".to_owned());
+
+        let expected = "a,1.0.0,b,1.0.0,\">=1.0, <2.0\",normal
+a,1.0.0,c,1.0.0,,normal
+";
+
+        assert_eq!(rows(&input), expected);
+    }
+
+    #[test]
+    fn rows_without_dependencies() {
+        assert_eq!(rows(&node("a", "1.0.0", &[])), "");
+    }
+
+    #[test]
+    fn field_quotes() {
+        assert_eq!(field("plain"), "plain");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(field("a\nb"), "\"a\nb\"");
+    }
+}
@@ -0,0 +1,335 @@
+use crate::domain::Crate;
+use crate::formats::{escape, purl};
+use semver::Version;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// A CycloneDX 1.4 bill of materials, serialising to the JSON format.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Bom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: Metadata,
+    components: Vec<Component>,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Metadata {
+    component: Component,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Component {
+    #[serde(rename = "type")]
+    component_type: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<License>,
+    purl: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Hash {
+    alg: &'static str,
+    content: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct License {
+    expression: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Dependency {
+    #[serde(rename = "ref")]
+    dependency_ref: String,
+    depends_on: Vec<String>,
+}
+
+/// Describes the dependency graph of crate+version, the root being the subject of the bill.
+pub(crate) fn bom(name: &str, version: &Version, crates: &[Crate]) -> Bom {
+    let root = crates
+        .iter()
+        .find(|c| c.name == name && &c.version == version);
+
+    Bom {
+        bom_format: "CycloneDX",
+        spec_version: "1.4",
+        version: 1,
+        metadata: Metadata {
+            component: match root {
+                Some(root) => component(root),
+                None => Component {
+                    component_type: "library",
+                    bom_ref: purl(name, version),
+                    name: name.to_owned(),
+                    version: version.to_string(),
+                    hashes: Vec::new(),
+                    licenses: Vec::new(),
+                    purl: purl(name, version),
+                },
+            },
+        },
+        components: crates
+            .iter()
+            .filter(|c| !(c.name == name && &c.version == version))
+            .map(component)
+            .collect(),
+        dependencies: crates
+            .iter()
+            .map(|c| Dependency {
+                dependency_ref: purl(&c.name, &c.version),
+                depends_on: c
+                    .dependency
+                    .iter()
+                    .map(|d| purl(&d.name, &d.version))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+/// Renders the bill in the XML format.
+pub(crate) fn xml(bom: &Bom) -> String {
+    let mut xml = String::new();
+
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        xml,
+        r#"<bom xmlns="http://cyclonedx.org/schema/bom/{}" version="{}">"#,
+        bom.spec_version, bom.version
+    )
+    .unwrap();
+
+    writeln!(xml, "  <metadata>").unwrap();
+    write_component(&mut xml, "    ", &bom.metadata.component);
+    writeln!(xml, "  </metadata>").unwrap();
+
+    writeln!(xml, "  <components>").unwrap();
+    for component in &bom.components {
+        write_component(&mut xml, "    ", component);
+    }
+    writeln!(xml, "  </components>").unwrap();
+
+    writeln!(xml, "  <dependencies>").unwrap();
+    for dependency in &bom.dependencies {
+        if dependency.depends_on.is_empty() {
+            writeln!(
+                xml,
+                r#"    <dependency ref="{}"/>"#,
+                escape(&dependency.dependency_ref)
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                xml,
+                r#"    <dependency ref="{}">"#,
+                escape(&dependency.dependency_ref)
+            )
+            .unwrap();
+            for depends_on in &dependency.depends_on {
+                writeln!(xml, r#"      <dependency ref="{}"/>"#, escape(depends_on)).unwrap();
+            }
+            writeln!(xml, "    </dependency>").unwrap();
+        }
+    }
+    writeln!(xml, "  </dependencies>").unwrap();
+
+    writeln!(xml, "</bom>").unwrap();
+
+    xml
+}
+
+fn component(c: &Crate) -> Component {
+    Component {
+        component_type: "library",
+        bom_ref: purl(&c.name, &c.version),
+        name: c.name.to_owned(),
+        version: c.version.to_string(),
+        hashes: c
+            .metadata
+            .checksum
+            .iter()
+            .map(|checksum| Hash {
+                alg: "SHA-256",
+                content: checksum.to_owned(),
+            })
+            .collect(),
+        licenses: c
+            .metadata
+            .license
+            .iter()
+            .map(|license| License {
+                expression: license.to_owned(),
+            })
+            .collect(),
+        purl: purl(&c.name, &c.version),
+    }
+}
+
+/// Elements in the order required by the CycloneDX schema.
+fn write_component(xml: &mut String, indent: &str, component: &Component) {
+    writeln!(
+        xml,
+        r#"{}<component type="{}" bom-ref="{}">"#,
+        indent,
+        component.component_type,
+        escape(&component.bom_ref)
+    )
+    .unwrap();
+    writeln!(xml, "{}  <name>{}</name>", indent, escape(&component.name)).unwrap();
+    writeln!(
+        xml,
+        "{}  <version>{}</version>",
+        indent,
+        escape(&component.version)
+    )
+    .unwrap();
+    if !component.hashes.is_empty() {
+        writeln!(xml, "{}  <hashes>", indent).unwrap();
+        for hash in &component.hashes {
+            writeln!(
+                xml,
+                r#"{}    <hash alg="{}">{}</hash>"#,
+                indent,
+                hash.alg,
+                escape(&hash.content)
+            )
+            .unwrap();
+        }
+        writeln!(xml, "{}  </hashes>", indent).unwrap();
+    }
+    if !component.licenses.is_empty() {
+        writeln!(xml, "{}  <licenses>", indent).unwrap();
+        for license in &component.licenses {
+            writeln!(
+                xml,
+                "{}    <expression>{}</expression>",
+                indent,
+                escape(&license.expression)
+            )
+            .unwrap();
+        }
+        writeln!(xml, "{}  </licenses>", indent).unwrap();
+    }
+    writeln!(xml, "{}  <purl>{}</purl>", indent, escape(&component.purl)).unwrap();
+    writeln!(xml, "{}</component>", indent).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    fn input() -> Vec<Crate> {
+        let mut input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0+build")]),
+            node("b", "1.0.0+build", &[]),
+        ];
+        input[1].metadata.license = Some("MIT OR Apache-2.0".to_owned());
+        input[1].metadata.checksum = Some("0123".to_owned());
+        input
+    }
+
+    #[test]
+    fn bom_json() {
+        let actual = serde_json::to_value(bom("a", &Version::new(1, 0, 0), &input())).unwrap();
+
+        let expected = serde_json::json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.4",
+            "version": 1,
+            "metadata": {
+                "component": {
+                    "type": "library",
+                    "bom-ref": "pkg:cargo/a@1.0.0",
+                    "name": "a",
+                    "version": "1.0.0",
+                    "purl": "pkg:cargo/a@1.0.0"
+                }
+            },
+            "components": [
+                {
+                    "type": "library",
+                    "bom-ref": "pkg:cargo/b@1.0.0%2Bbuild",
+                    "name": "b",
+                    "version": "1.0.0+build",
+                    "hashes": [{"alg": "SHA-256", "content": "0123"}],
+                    "licenses": [{"expression": "MIT OR Apache-2.0"}],
+                    "purl": "pkg:cargo/b@1.0.0%2Bbuild"
+                }
+            ],
+            "dependencies": [
+                {"ref": "pkg:cargo/a@1.0.0", "dependsOn": ["pkg:cargo/b@1.0.0%2Bbuild"]},
+                {"ref": "pkg:cargo/b@1.0.0%2Bbuild", "dependsOn": []}
+            ]
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn bom_xml() {
+        let actual = xml(&bom("a", &Version::new(1, 0, 0), &input()));
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<bom xmlns="http://cyclonedx.org/schema/bom/1.4" version="1">
+  <metadata>
+    <component type="library" bom-ref="pkg:cargo/a@1.0.0">
+      <name>a</name>
+      <version>1.0.0</version>
+      <purl>pkg:cargo/a@1.0.0</purl>
+    </component>
+  </metadata>
+  <components>
+    <component type="library" bom-ref="pkg:cargo/b@1.0.0%2Bbuild">
+      <name>b</name>
+      <version>1.0.0+build</version>
+      <hashes>
+        <hash alg="SHA-256">0123</hash>
+      </hashes>
+      <licenses>
+        <expression>MIT OR Apache-2.0</expression>
+      </licenses>
+      <purl>pkg:cargo/b@1.0.0%2Bbuild</purl>
+    </component>
+  </components>
+  <dependencies>
+    <dependency ref="pkg:cargo/a@1.0.0">
+      <dependency ref="pkg:cargo/b@1.0.0%2Bbuild"/>
+    </dependency>
+    <dependency ref="pkg:cargo/b@1.0.0%2Bbuild"/>
+  </dependencies>
+</bom>
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn bom_xml_escapes_and_omits_missing_metadata() {
+        let mut input = vec![node("b", "1.0.0-alpha+build", &[])];
+        input[0].metadata.license = Some("MIT AND <custom> & 'other'".to_owned());
+
+        // the root is not part of the graph, so is described by name and version only.
+        let actual = xml(&bom("a", &Version::new(1, 0, 0), &input));
+
+        assert!(actual.contains(
+            "    <component type=\"library\" bom-ref=\"pkg:cargo/a@1.0.0\">\n      <name>a</name>\n      <version>1.0.0</version>\n      <purl>pkg:cargo/a@1.0.0</purl>\n"
+        ));
+        assert!(actual.contains(
+            "        <expression>MIT AND &lt;custom&gt; &amp; &apos;other&apos;</expression>\n"
+        ));
+        assert!(actual.contains("<version>1.0.0-alpha+build</version>"));
+        assert!(!actual.contains("<hashes>"));
+    }
+}
@@ -0,0 +1,318 @@
+use crate::domain::Crate;
+use crate::formats::versions;
+use crate::graph::GraphDiff;
+use semver::Version;
+use std::collections::{BTreeSet, HashMap};
+
+/// A diagram independent of its notation, rendered by `mermaid` and `plantuml`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Diagram {
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) edges: Vec<Edge>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Node {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) class: Option<Class>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Edge {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    pub(crate) dashed: bool,
+    pub(crate) label: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Class {
+    Root,
+    Leaves,
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Class {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Class::Root => "root",
+            Class::Leaves => "leaves",
+            Class::Added => "added",
+            Class::Removed => "removed",
+            Class::Changed => "changed",
+        }
+    }
+
+    pub(crate) fn fill(self) -> &'static str {
+        match self {
+            Class::Root => "#ddf",
+            Class::Leaves => "#eee",
+            Class::Added => "#dfd",
+            Class::Removed => "#fdd",
+            Class::Changed => "#ffd",
+        }
+    }
+}
+
+/// Diagram of the dependency graph of crate+version. Optional edges are labelled as such. With
+/// `collapse_leaves`, the leaf dependencies of a crate depending on more than one leaf are drawn
+/// as a single node.
+pub(crate) fn graph(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+    collapse_leaves: bool,
+) -> Diagram {
+    let ids = crates
+        .iter()
+        .enumerate()
+        .map(|(index, c)| ((&c.name, &c.version), format!("n{}", index)))
+        .collect::<HashMap<_, _>>();
+
+    let leaves = crates
+        .iter()
+        .filter(|c| c.dependency.is_empty())
+        .map(|c| (&c.name, &c.version))
+        .collect::<BTreeSet<_>>();
+
+    let mut edges = Vec::new();
+    let mut collapsed = Vec::new();
+
+    for c in crates {
+        let from = &ids[&(&c.name, &c.version)];
+
+        let dependency = c
+            .dependency
+            .iter()
+            .filter(|d| ids.contains_key(&(&d.name, &d.version)))
+            .collect::<Vec<_>>();
+
+        let leaf_count = dependency
+            .iter()
+            .filter(|d| leaves.contains(&(&d.name, &d.version)))
+            .count();
+
+        let collapse = collapse_leaves && leaf_count > 1;
+
+        for d in dependency {
+            if collapse && leaves.contains(&(&d.name, &d.version)) {
+                continue;
+            }
+
+            edges.push(Edge {
+                from: from.to_owned(),
+                to: ids[&(&d.name, &d.version)].to_owned(),
+                dashed: false,
+                label: if d.optional {
+                    Some("optional".to_owned())
+                } else {
+                    None
+                },
+            });
+        }
+
+        if collapse {
+            let id = format!("{}_leaves", from);
+
+            collapsed.push(Node {
+                id: id.to_owned(),
+                label: format!("{} leaf crates", leaf_count),
+                class: Some(Class::Leaves),
+            });
+            edges.push(Edge {
+                from: from.to_owned(),
+                to: id,
+                dashed: false,
+                label: None,
+            });
+        }
+    }
+
+    let mut nodes = crates
+        .iter()
+        .filter(|c| {
+            let id = &ids[&(&c.name, &c.version)];
+            let root = c.name == name && &c.version == version;
+
+            root || edges.iter().any(|e| &e.to == id)
+        })
+        .map(|c| Node {
+            id: ids[&(&c.name, &c.version)].to_owned(),
+            label: format!("{} {}", c.name, c.version),
+            class: if c.name == name && &c.version == version {
+                Some(Class::Root)
+            } else {
+                None
+            },
+        })
+        .collect::<Vec<_>>();
+
+    nodes.append(&mut collapsed);
+
+    Diagram { nodes, edges }
+}
+
+/// Diagram of the changes between two graphs, by crate name. Crates are classed as added,
+/// removed or changed, unchanged crates only appearing as the parent of a changed edge.
+pub(crate) fn diff(diff: &GraphDiff) -> Diagram {
+    let mut classes = HashMap::new();
+    let mut labels = HashMap::new();
+
+    for (changes, class) in &[
+        (&diff.added_nodes, Class::Added),
+        (&diff.removed_nodes, Class::Removed),
+        (&diff.changed_nodes, Class::Changed),
+    ] {
+        for change in changes.iter() {
+            classes.insert(change.name.as_str(), *class);
+            labels.insert(
+                change.name.as_str(),
+                format!("{} {}", change.name, versions(&change.from, &change.to)),
+            );
+        }
+    }
+
+    let mut names = classes.keys().copied().collect::<BTreeSet<_>>();
+
+    let changes = diff
+        .added_edges
+        .iter()
+        .map(|change| (change, "added", false))
+        .chain(
+            diff.removed_edges
+                .iter()
+                .map(|change| (change, "removed", true)),
+        )
+        .chain(
+            diff.changed_edges
+                .iter()
+                .map(|change| (change, "changed", false)),
+        )
+        .collect::<Vec<_>>();
+
+    for (change, _, _) in &changes {
+        names.insert(change.parent.as_str());
+        names.insert(change.child.as_str());
+    }
+
+    let ids = names
+        .iter()
+        .enumerate()
+        .map(|(index, &name)| (name, format!("n{}", index)))
+        .collect::<HashMap<_, _>>();
+
+    Diagram {
+        nodes: names
+            .iter()
+            .map(|&name| Node {
+                id: ids[name].to_owned(),
+                label: labels.get(name).cloned().unwrap_or_else(|| name.to_owned()),
+                class: classes.get(name).copied(),
+            })
+            .collect(),
+        edges: changes
+            .iter()
+            .map(|(change, kind, dashed)| Edge {
+                from: ids[change.parent.as_str()].to_owned(),
+                to: ids[change.child.as_str()].to_owned(),
+                dashed: *dashed,
+                label: Some(format!("{} {}", kind, versions(&change.from, &change.to))),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+    use crate::test_support::node;
+
+    #[test]
+    fn graph_collapse_leaves() {
+        let mut input = vec![
+            node(
+                "a",
+                "1.0.0",
+                &[("b", "1.0.0"), ("c", "1.0.0"), ("d", "1.0.0")],
+            ),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[]),
+            node("d", "1.0.0", &[]),
+        ];
+        input[1].dependency[0].optional = true;
+
+        let actual = graph("a", &Version::new(1, 0, 0), &input, true);
+
+        assert_eq!(
+            actual
+                .nodes
+                .iter()
+                .map(|n| n.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a 1.0.0", "b 1.0.0", "c 1.0.0", "2 leaf crates"]
+        );
+        assert_eq!(
+            actual.edges,
+            vec![
+                Edge {
+                    from: "n0".to_owned(),
+                    to: "n1".to_owned(),
+                    dashed: false,
+                    label: None,
+                },
+                Edge {
+                    from: "n0".to_owned(),
+                    to: "n0_leaves".to_owned(),
+                    dashed: false,
+                    label: None,
+                },
+                Edge {
+                    from: "n1".to_owned(),
+                    to: "n2".to_owned(),
+                    dashed: false,
+                    label: Some("optional".to_owned()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_changes() {
+        let from = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+        ];
+        let to = vec![
+            node("a", "1.0.0", &[("b", "2.0.0"), ("c", "1.0.0")]),
+            node("b", "2.0.0", &[]),
+            node("c", "1.0.0", &[]),
+        ];
+
+        let actual = diff(&graph::diff(&from, &to));
+
+        assert_eq!(
+            actual
+                .nodes
+                .iter()
+                .map(|n| (n.label.as_str(), n.class))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a", None),
+                ("b 1.0.0 -> 2.0.0", Some(Class::Changed)),
+                ("c 1.0.0", Some(Class::Added)),
+            ]
+        );
+        assert_eq!(
+            actual
+                .edges
+                .iter()
+                .map(|e| e.label.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["added 1.0.0", "changed 1.0.0 -> 2.0.0"]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::domain::{Crate, CrateDependency};
+use crate::graph;
+use crate::graph::Rules;
+use semver::Version;
+use std::fmt::Write;
+
+/// Renders the dependency graph of crate+version as a Graphviz digraph. Optional edges are gray,
+/// the root is bold and pruned crates are dashed.
+/// With `cluster`, the versions of every duplicate crate name are grouped together.
+pub(crate) fn graph(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+    rules: &Rules,
+    cluster: bool,
+) -> String {
+    let mut dot = String::new();
+
+    writeln!(dot, "digraph {} {{", quote(&id(name, version))).unwrap();
+    writeln!(dot, "    node [shape=box];").unwrap();
+
+    let duplicates = if cluster {
+        graph::duplicates(crates)
+            .into_iter()
+            .map(|d| d.name)
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
+    for duplicate in &duplicates {
+        writeln!(dot).unwrap();
+        writeln!(
+            dot,
+            "    subgraph {} {{",
+            quote(&format!("cluster_{}", duplicate))
+        )
+        .unwrap();
+        writeln!(dot, "        label={};", quote(duplicate)).unwrap();
+        for c in crates.iter().filter(|c| &c.name == duplicate) {
+            writeln!(dot, "        {};", node(name, version, c, rules)).unwrap();
+        }
+        writeln!(dot, "    }}").unwrap();
+    }
+
+    writeln!(dot).unwrap();
+    for c in crates.iter().filter(|c| !duplicates.contains(&c.name)) {
+        writeln!(dot, "    {};", node(name, version, c, rules)).unwrap();
+    }
+
+    writeln!(dot).unwrap();
+    for c in crates {
+        for d in &c.dependency {
+            writeln!(dot, "    {};", edge(c, d)).unwrap();
+        }
+    }
+
+    writeln!(dot, "}}").unwrap();
+
+    dot
+}
+
+fn node(name: &str, version: &Version, c: &Crate, rules: &Rules) -> String {
+    let mut attributes = vec![format!(
+        "label={}",
+        quote(&format!("{}\n{}", c.name, c.version))
+    )];
+
+    if c.name == name && &c.version == version {
+        attributes.push("penwidth=2".to_owned());
+    }
+    if rules.prunes(&c.name) {
+        attributes.push("style=dashed".to_owned());
+    }
+
+    format!(
+        "{} [{}]",
+        quote(&id(&c.name, &c.version)),
+        attributes.join(", ")
+    )
+}
+
+fn edge(c: &Crate, d: &CrateDependency) -> String {
+    let edge = format!(
+        "{} -> {}",
+        quote(&id(&c.name, &c.version)),
+        quote(&id(&d.name, &d.version))
+    );
+
+    if d.optional {
+        format!("{} [color=gray]", edge)
+    } else {
+        edge
+    }
+}
+
+fn id(name: &str, version: &Version) -> String {
+    format!("{} {}", name, version)
+}
+
+fn quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn graph_digraph() {
+        let mut input = vec![
+            node(
+                "a",
+                "1.0.0",
+                &[("b", "1.0.0"), ("c", "1.0.0"), ("c", "2.0.0")],
+            ),
+            node("b", "1.0.0", &[]),
+            node("c", "1.0.0", &[]),
+            node("c", "2.0.0", &[]),
+        ];
+        input[0].dependency[2].optional = true;
+
+        let rules = Rules::new(Vec::new(), vec!["b".to_owned()]);
+
+        let actual = graph("a", &Version::new(1, 0, 0), &input, &rules, true);
+
+        let expected = r#"digraph "a 1.0.0" {
+    node [shape=box];
+
+    subgraph "cluster_c" {
+        label="c";
+        "c 1.0.0" [label="c\n1.0.0"];
+        "c 2.0.0" [label="c\n2.0.0"];
+    }
+
+    "a 1.0.0" [label="a\n1.0.0", penwidth=2];
+    "b 1.0.0" [label="b\n1.0.0", style=dashed];
+
+    "a 1.0.0" -> "b 1.0.0";
+    "a 1.0.0" -> "c 1.0.0";
+    "a 1.0.0" -> "c 2.0.0" [color=gray];
+}
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn quote_escapes() {
+        assert_eq!(quote("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+    }
+}
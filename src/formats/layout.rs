@@ -0,0 +1,339 @@
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::{HashMap, VecDeque};
+
+pub(crate) const NODE_HEIGHT: f64 = 36.0;
+const LAYER_GAP: f64 = 48.0;
+const NODE_GAP: f64 = 16.0;
+const MARGIN: f64 = 16.0;
+/// Width of the vertices routing an edge across a layer.
+const DUMMY_WIDTH: f64 = 8.0;
+/// Barycenter sweeps, each going down then up the layers.
+const SWEEPS: usize = 4;
+
+/// Layered drawing of a dependency graph, coordinates in pixels from the top left corner.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Layout {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) nodes: Vec<NodeBox>,
+    pub(crate) edges: Vec<EdgePath>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct NodeBox {
+    /// Index of the crate.
+    pub(crate) index: usize,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct EdgePath {
+    /// Index of the depending crate.
+    pub(crate) from: usize,
+    /// Index of the dependency within the depending crate.
+    pub(crate) dependency: usize,
+    /// Polyline from the depending crate to the dependency.
+    pub(crate) points: Vec<(f64, f64)>,
+}
+
+struct Vertex {
+    layer: usize,
+    width: f64,
+}
+
+/// Lays out the dependency graph of crate+version Sugiyama style: cycles are broken by reversing
+/// back edges, crates are layered by longest path from the root, edges spanning several layers
+/// are routed through dummy vertices, and crossings are reduced by barycenter ordering.
+pub(crate) fn layout(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+    width: impl Fn(&Crate) -> f64,
+) -> Layout {
+    let index = crates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| ((c.name.as_str(), &c.version), i))
+        .collect::<HashMap<_, _>>();
+
+    // (from, dependency, to) of the edges between crates of the graph.
+    let edges = crates
+        .iter()
+        .enumerate()
+        .flat_map(|(from, c)| {
+            let index = &index;
+            c.dependency.iter().enumerate().filter_map(move |(d, dep)| {
+                index
+                    .get(&(dep.name.as_str(), &dep.version))
+                    .map(|&to| (from, d, to))
+            })
+        })
+        .filter(|(from, _, to)| from != to)
+        .collect::<Vec<_>>();
+
+    let root = index.get(&(name, version)).copied().unwrap_or(0);
+    let reversed = back_edges(crates.len(), root, &edges);
+
+    let forward = edges
+        .iter()
+        .zip(&reversed)
+        .map(|(&(from, _, to), &reversed)| if reversed { (to, from) } else { (from, to) })
+        .collect::<Vec<_>>();
+
+    let layers = layers(crates.len(), &forward);
+
+    let mut vertices = crates
+        .iter()
+        .zip(&layers)
+        .map(|(c, &layer)| Vertex {
+            layer,
+            width: width(c),
+        })
+        .collect::<Vec<_>>();
+
+    // every edge becomes a chain of vertices, one per layer it spans.
+    let mut chains = Vec::new();
+    let mut up = vec![Vec::new(); vertices.len()];
+    let mut down = vec![Vec::new(); vertices.len()];
+
+    for &(from, to) in &forward {
+        let mut chain = vec![from];
+
+        for layer in layers[from] + 1..layers[to] {
+            vertices.push(Vertex {
+                layer,
+                width: DUMMY_WIDTH,
+            });
+            up.push(Vec::new());
+            down.push(Vec::new());
+            chain.push(vertices.len() - 1);
+        }
+        chain.push(to);
+
+        for pair in chain.windows(2) {
+            down[pair[0]].push(pair[1]);
+            up[pair[1]].push(pair[0]);
+        }
+        chains.push(chain);
+    }
+
+    let layer_count = vertices.iter().map(|v| v.layer + 1).max().unwrap_or(0);
+    let mut order = vec![Vec::new(); layer_count];
+    for (v, vertex) in vertices.iter().enumerate() {
+        order[vertex.layer].push(v);
+    }
+
+    let mut position = vec![0; vertices.len()];
+    update_positions(&order, &mut position);
+
+    for _ in 0..SWEEPS {
+        for layer in 1..layer_count {
+            reorder(&mut order[layer], &up, &position);
+            update_positions(&order, &mut position);
+        }
+        for layer in (0..layer_count.saturating_sub(1)).rev() {
+            reorder(&mut order[layer], &down, &position);
+            update_positions(&order, &mut position);
+        }
+    }
+
+    let layer_widths = order
+        .iter()
+        .map(|layer| {
+            layer.iter().map(|&v| vertices[v].width).sum::<f64>()
+                + NODE_GAP * layer.len().saturating_sub(1) as f64
+        })
+        .collect::<Vec<_>>();
+    let content_width = layer_widths.iter().copied().fold(0.0, f64::max);
+
+    // layers are centered on the widest.
+    let mut x = vec![0.0; vertices.len()];
+    for (layer, layer_width) in order.iter().zip(&layer_widths) {
+        let mut left = MARGIN + (content_width - layer_width) / 2.0;
+        for &v in layer {
+            x[v] = left;
+            left += vertices[v].width + NODE_GAP;
+        }
+    }
+    let y = |v: usize| MARGIN + vertices[v].layer as f64 * (NODE_HEIGHT + LAYER_GAP);
+    let center = |v: usize| x[v] + vertices[v].width / 2.0;
+
+    let edges = edges
+        .iter()
+        .zip(&reversed)
+        .zip(&chains)
+        .map(|((&(from, dependency, _), &reversed), chain)| {
+            let last = chain.len() - 1;
+            let mut points = Vec::new();
+
+            for (i, &v) in chain.iter().enumerate() {
+                if i > 0 {
+                    points.push((center(v), y(v)));
+                }
+                if i < last {
+                    points.push((center(v), y(v) + NODE_HEIGHT));
+                }
+            }
+            if reversed {
+                points.reverse();
+            }
+
+            EdgePath {
+                from,
+                dependency,
+                points,
+            }
+        })
+        .collect();
+
+    Layout {
+        width: content_width + 2.0 * MARGIN,
+        height: if layer_count == 0 {
+            2.0 * MARGIN
+        } else {
+            2.0 * MARGIN + layer_count as f64 * NODE_HEIGHT + (layer_count - 1) as f64 * LAYER_GAP
+        },
+        nodes: (0..crates.len())
+            .map(|index| NodeBox {
+                index,
+                x: x[index],
+                y: y(index),
+                width: vertices[index].width,
+            })
+            .collect(),
+        edges,
+    }
+}
+
+/// Marks the edges closing a cycle, found depth first from the root then from any crate left.
+fn back_edges(count: usize, root: usize, edges: &[(usize, usize, usize)]) -> Vec<bool> {
+    let mut out = vec![Vec::new(); count];
+    for (e, &(from, _, to)) in edges.iter().enumerate() {
+        out[from].push((e, to));
+    }
+
+    // 0 unvisited, 1 on the stack, 2 done.
+    let mut state = vec![0u8; count];
+    let mut reversed = vec![false; edges.len()];
+
+    for start in std::iter::once(root).chain(0..count) {
+        if start >= count || state[start] != 0 {
+            continue;
+        }
+
+        state[start] = 1;
+        let mut stack = vec![(start, 0)];
+
+        while let Some((v, next)) = stack.pop() {
+            if let Some(&(e, to)) = out[v].get(next) {
+                stack.push((v, next + 1));
+                match state[to] {
+                    0 => {
+                        state[to] = 1;
+                        stack.push((to, 0));
+                    }
+                    1 => reversed[e] = true,
+                    _ => {}
+                }
+            } else {
+                state[v] = 2;
+            }
+        }
+    }
+
+    reversed
+}
+
+/// Layer of every crate, the longest path to it from a crate without dependents.
+fn layers(count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut out = vec![Vec::new(); count];
+    let mut incoming = vec![0; count];
+    for &(from, to) in edges {
+        out[from].push(to);
+        incoming[to] += 1;
+    }
+
+    let mut layers = vec![0; count];
+    let mut queue = (0..count)
+        .filter(|&v| incoming[v] == 0)
+        .collect::<VecDeque<_>>();
+
+    while let Some(v) = queue.pop_front() {
+        for &to in &out[v] {
+            layers[to] = layers[to].max(layers[v] + 1);
+            incoming[to] -= 1;
+            if incoming[to] == 0 {
+                queue.push_back(to);
+            }
+        }
+    }
+
+    layers
+}
+
+/// Sorts a layer by the mean position of the neighbours in the adjacent layer, vertices without
+/// neighbours keeping their place.
+fn reorder(layer: &mut Vec<usize>, neighbours: &[Vec<usize>], position: &[usize]) {
+    let mut keyed = layer
+        .iter()
+        .map(|&v| {
+            let key = if neighbours[v].is_empty() {
+                position[v] as f64
+            } else {
+                neighbours[v]
+                    .iter()
+                    .map(|&n| position[n] as f64)
+                    .sum::<f64>()
+                    / neighbours[v].len() as f64
+            };
+            (key, v)
+        })
+        .collect::<Vec<_>>();
+
+    keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    *layer = keyed.into_iter().map(|(_, v)| v).collect();
+}
+
+fn update_positions(order: &[Vec<usize>], position: &mut [usize]) {
+    for layer in order {
+        for (i, &v) in layer.iter().enumerate() {
+            position[v] = i;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn layout_layers() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[("b", "1.0.0")]),
+        ];
+
+        let actual = layout("a", &Version::new(1, 0, 0), &input, |_| 40.0);
+
+        // b -> c is kept, c -> b closes the cycle and is drawn upwards.
+        assert_eq!(
+            actual.nodes.iter().map(|n| (n.x, n.y)).collect::<Vec<_>>(),
+            vec![(28.0, 16.0), (16.0, 100.0), (28.0, 184.0)]
+        );
+        assert_eq!(actual.width, 96.0);
+        assert_eq!(actual.height, 236.0);
+
+        // a -> c spans two layers, so is routed through a dummy vertex next to b.
+        assert_eq!(
+            actual.edges[1].points,
+            vec![(48.0, 52.0), (76.0, 100.0), (76.0, 136.0), (48.0, 184.0)]
+        );
+        assert_eq!(actual.edges[3].points, vec![(48.0, 184.0), (36.0, 136.0)]);
+    }
+}
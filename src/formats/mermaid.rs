@@ -0,0 +1,118 @@
+use crate::formats::diagram::Diagram;
+use std::fmt::Write;
+
+/// Renders a diagram as a Mermaid flowchart, top down.
+pub(crate) fn render(diagram: &Diagram) -> String {
+    let mut text = String::new();
+
+    writeln!(text, "graph TD").unwrap();
+
+    for node in &diagram.nodes {
+        write!(text, "    {}[\"{}\"]", node.id, escape(&node.label)).unwrap();
+        if let Some(class) = node.class {
+            write!(text, ":::{}", class.name()).unwrap();
+        }
+        writeln!(text).unwrap();
+    }
+
+    for edge in &diagram.edges {
+        let arrow = if edge.dashed { "-.->" } else { "-->" };
+
+        match &edge.label {
+            Some(label) => writeln!(
+                text,
+                "    {} {}|\"{}\"| {}",
+                edge.from,
+                arrow,
+                escape(label),
+                edge.to
+            ),
+            None => writeln!(text, "    {} {} {}", edge.from, arrow, edge.to),
+        }
+        .unwrap();
+    }
+
+    let mut classes = Vec::new();
+    for class in diagram.nodes.iter().filter_map(|n| n.class) {
+        if !classes.contains(&class) {
+            classes.push(class);
+        }
+    }
+
+    for class in classes {
+        writeln!(text, "    classDef {} fill:{}", class.name(), class.fill()).unwrap();
+    }
+
+    text
+}
+
+fn escape(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::diagram;
+    use crate::test_support::node;
+    use semver::Version;
+
+    #[test]
+    fn render_graph() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[]),
+        ];
+
+        let actual = render(&diagram::graph("a", &Version::new(1, 0, 0), &input, false));
+
+        let expected = r#"graph TD
+    n0["a 1.0.0"]:::root
+    n1["b 1.0.0"]
+    n2["c 1.0.0"]
+    n0 --> n1
+    n1 --> n2
+    classDef root fill:#ddf
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn render_optional_and_collapsed_leaves() {
+        let mut input = vec![
+            node(
+                "a",
+                "1.0.0",
+                &[("b", "1.0.0"), ("d", "1.0.0"), ("e", "1.0.0")],
+            ),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[]),
+            node("d", "1.0.0", &[]),
+            node("e", "1.0.0", &[]),
+        ];
+        input[0].dependency[0].optional = true;
+
+        let actual = render(&diagram::graph("a", &Version::new(1, 0, 0), &input, true));
+
+        let expected = r#"graph TD
+    n0["a 1.0.0"]:::root
+    n1["b 1.0.0"]
+    n2["c 1.0.0"]
+    n0_leaves["2 leaf crates"]:::leaves
+    n0 -->|"optional"| n1
+    n0 --> n0_leaves
+    n1 --> n2
+    classDef root fill:#ddf
+    classDef leaves fill:#eee
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn escape_quotes() {
+        assert_eq!(escape(r#"say "hi""#), "say #quot;hi#quot;");
+    }
+}
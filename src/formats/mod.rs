@@ -0,0 +1,49 @@
+pub(crate) mod csv;
+pub(crate) mod cyclonedx;
+pub(crate) mod diagram;
+pub(crate) mod dot;
+mod layout;
+pub(crate) mod mermaid;
+pub(crate) mod plantuml;
+pub(crate) mod spdx;
+pub(crate) mod svg;
+pub(crate) mod text;
+pub(crate) mod tree;
+
+use semver::Version;
+
+/// Package URL of a crate, `+` being reserved in the version of a purl.
+fn purl(name: &str, version: &Version) -> String {
+    format!(
+        "pkg:cargo/{}@{}",
+        name,
+        version.to_string().replace('+', "%2B")
+    )
+}
+
+/// Escapes text and attribute values of XML documents.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Versions of a changed crate, `from -> to` when both sides have some.
+fn versions(from: &[Version], to: &[Version]) -> String {
+    let join = |versions: &[Version]| {
+        versions
+            .iter()
+            .map(Version::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match (from.is_empty(), to.is_empty()) {
+        (true, _) => join(to),
+        (_, true) => join(from),
+        _ => format!("{} -> {}", join(from), join(to)),
+    }
+}
@@ -0,0 +1,90 @@
+use crate::formats::diagram::Diagram;
+use std::fmt::Write;
+
+/// Renders a diagram as a PlantUML diagram of rectangles.
+pub(crate) fn render(diagram: &Diagram) -> String {
+    let mut text = String::new();
+
+    writeln!(text, "@startuml").unwrap();
+
+    for node in &diagram.nodes {
+        write!(text, "rectangle \"{}\" as {}", escape(&node.label), node.id).unwrap();
+        if let Some(class) = node.class {
+            write!(text, " {}", class.fill()).unwrap();
+        }
+        writeln!(text).unwrap();
+    }
+
+    for edge in &diagram.edges {
+        let arrow = if edge.dashed { "..>" } else { "-->" };
+
+        match &edge.label {
+            Some(label) => writeln!(text, "{} {} {} : {}", edge.from, arrow, edge.to, label),
+            None => writeln!(text, "{} {} {}", edge.from, arrow, edge.to),
+        }
+        .unwrap();
+    }
+
+    writeln!(text, "@enduml").unwrap();
+
+    text
+}
+
+fn escape(value: &str) -> String {
+    value.replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::diagram;
+    use crate::graph;
+    use crate::test_support::node;
+    use semver::Version;
+
+    #[test]
+    fn render_diff() {
+        let from = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+        ];
+        let to = vec![node("a", "1.1.0", &[]), node("c", "1.0.0", &[])];
+
+        let actual = render(&diagram::diff(&graph::diff(&from, &to)));
+
+        let expected = r#"@startuml
+rectangle "a 1.0.0 -> 1.1.0" as n0 #ffd
+rectangle "b 1.0.0" as n1 #fdd
+rectangle "c 1.0.0" as n2 #dfd
+n0 ..> n1 : removed 1.0.0
+@enduml
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn render_optional_edge() {
+        let mut input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+        ];
+        input[0].dependency[0].optional = true;
+
+        let actual = render(&diagram::graph("a", &Version::new(1, 0, 0), &input, false));
+
+        let expected = r#"@startuml
+rectangle "a 1.0.0" as n0 #ddf
+rectangle "b 1.0.0" as n1
+n0 --> n1 : optional
+@enduml
+"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn escape_quotes() {
+        assert_eq!(escape(r#"say "hi""#), r#"say \"hi\""#);
+    }
+}
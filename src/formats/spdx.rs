@@ -0,0 +1,353 @@
+use crate::domain::Crate;
+use crate::formats::purl;
+use semver::Version;
+use serde::Serialize;
+use std::fmt::Write;
+
+const NOASSERTION: &str = "NOASSERTION";
+
+/// An SPDX 2.3 document, serialising to the JSON format.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Document {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<Package>,
+    relationships: Vec<Relationship>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    name: String,
+    version_info: String,
+    download_location: String,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checksums: Vec<Checksum>,
+    license_concluded: &'static str,
+    license_declared: String,
+    copyright_text: &'static str,
+    external_refs: Vec<ExternalRef>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checksum {
+    algorithm: &'static str,
+    checksum_value: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+/// Describes the dependency graph of crate+version, created at `created` (RFC 3339, UTC).
+pub(crate) fn document(name: &str, version: &Version, crates: &[Crate], created: &str) -> Document {
+    let document_name = format!("{}-{}", name, version);
+
+    let mut relationships = vec![Relationship {
+        spdx_element_id: "SPDXRef-DOCUMENT".to_owned(),
+        relationship_type: "DESCRIBES",
+        related_spdx_element: spdx_id(name, version),
+    }];
+
+    for c in crates {
+        for d in &c.dependency {
+            relationships.push(Relationship {
+                spdx_element_id: spdx_id(&c.name, &c.version),
+                relationship_type: "DEPENDS_ON",
+                related_spdx_element: spdx_id(&d.name, &d.version),
+            });
+        }
+    }
+
+    Document {
+        spdx_version: "SPDX-2.3",
+        data_license: "CC0-1.0",
+        spdx_id: "SPDXRef-DOCUMENT",
+        // the creation time keeps the namespace unique between documents of the same graph.
+        document_namespace: format!(
+            "https://spdx.org/spdxdocs/{}-{}",
+            document_name,
+            created.replace(':', "")
+        ),
+        name: document_name,
+        creation_info: CreationInfo {
+            created: created.to_owned(),
+            creators: vec![format!(
+                "Tool: {}-{}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            )],
+        },
+        packages: crates.iter().map(package).collect(),
+        relationships,
+    }
+}
+
+/// Renders the document in the tag-value format.
+pub(crate) fn tag_value(document: &Document) -> String {
+    let mut text = String::new();
+
+    writeln!(text, "SPDXVersion: {}", document.spdx_version).unwrap();
+    writeln!(text, "DataLicense: {}", document.data_license).unwrap();
+    writeln!(text, "SPDXID: {}", document.spdx_id).unwrap();
+    writeln!(text, "DocumentName: {}", document.name).unwrap();
+    writeln!(text, "DocumentNamespace: {}", document.document_namespace).unwrap();
+    for creator in &document.creation_info.creators {
+        writeln!(text, "Creator: {}", creator).unwrap();
+    }
+    writeln!(text, "Created: {}", document.creation_info.created).unwrap();
+
+    for package in &document.packages {
+        writeln!(text).unwrap();
+        writeln!(text, "PackageName: {}", package.name).unwrap();
+        writeln!(text, "SPDXID: {}", package.spdx_id).unwrap();
+        writeln!(text, "PackageVersion: {}", package.version_info).unwrap();
+        writeln!(
+            text,
+            "PackageDownloadLocation: {}",
+            package.download_location
+        )
+        .unwrap();
+        writeln!(text, "FilesAnalyzed: {}", package.files_analyzed).unwrap();
+        for checksum in &package.checksums {
+            writeln!(
+                text,
+                "PackageChecksum: {}: {}",
+                checksum.algorithm, checksum.checksum_value
+            )
+            .unwrap();
+        }
+        writeln!(
+            text,
+            "PackageLicenseConcluded: {}",
+            package.license_concluded
+        )
+        .unwrap();
+        writeln!(text, "PackageLicenseDeclared: {}", package.license_declared).unwrap();
+        writeln!(text, "PackageCopyrightText: {}", package.copyright_text).unwrap();
+        for external_ref in &package.external_refs {
+            writeln!(
+                text,
+                "ExternalRef: {} {} {}",
+                external_ref.reference_category,
+                external_ref.reference_type,
+                external_ref.reference_locator
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(text).unwrap();
+    for relationship in &document.relationships {
+        writeln!(
+            text,
+            "Relationship: {} {} {}",
+            relationship.spdx_element_id,
+            relationship.relationship_type,
+            relationship.related_spdx_element
+        )
+        .unwrap();
+    }
+
+    text
+}
+
+fn package(c: &Crate) -> Package {
+    Package {
+        spdx_id: spdx_id(&c.name, &c.version),
+        name: c.name.to_owned(),
+        version_info: c.version.to_string(),
+        download_location: format!(
+            "https://crates.io/api/v1/crates/{}/{}/download",
+            c.name, c.version
+        ),
+        files_analyzed: false,
+        checksums: c
+            .metadata
+            .checksum
+            .iter()
+            .map(|checksum| Checksum {
+                algorithm: "SHA256",
+                checksum_value: checksum.to_owned(),
+            })
+            .collect(),
+        license_concluded: NOASSERTION,
+        license_declared: c
+            .metadata
+            .license
+            .to_owned()
+            .unwrap_or_else(|| NOASSERTION.to_owned()),
+        copyright_text: NOASSERTION,
+        external_refs: vec![ExternalRef {
+            reference_category: "PACKAGE-MANAGER",
+            reference_type: "purl",
+            reference_locator: purl(&c.name, &c.version),
+        }],
+    }
+}
+
+/// SPDX identifiers only allow letters, numbers, `.` and `-`.
+fn spdx_id(name: &str, version: &Version) -> String {
+    format!("SPDXRef-Package-cargo-{}-{}", name, version)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    fn input() -> Vec<Crate> {
+        let mut input = vec![
+            node("a", "1.0.0", &[("b_c", "1.0.0")]),
+            node("b_c", "1.0.0", &[]),
+        ];
+        input[1].metadata.license = Some("MIT OR Apache-2.0".to_owned());
+        input[1].metadata.checksum = Some("0123".to_owned());
+        input
+    }
+
+    #[test]
+    fn document_tag_value() {
+        let actual = tag_value(&document(
+            "a",
+            &Version::new(1, 0, 0),
+            &input(),
+            "2020-10-01T00:00:00Z",
+        ));
+
+        let expected = format!(
+            "SPDXVersion: SPDX-2.3
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: a-1.0.0
+DocumentNamespace: https://spdx.org/spdxdocs/a-1.0.0-2020-10-01T000000Z
+Creator: Tool: {}-{}
+Created: 2020-10-01T00:00:00Z
+
+PackageName: a
+SPDXID: SPDXRef-Package-cargo-a-1.0.0
+PackageVersion: 1.0.0
+PackageDownloadLocation: https://crates.io/api/v1/crates/a/1.0.0/download
+FilesAnalyzed: false
+PackageLicenseConcluded: NOASSERTION
+PackageLicenseDeclared: NOASSERTION
+PackageCopyrightText: NOASSERTION
+ExternalRef: PACKAGE-MANAGER purl pkg:cargo/a@1.0.0
+
+PackageName: b_c
+SPDXID: SPDXRef-Package-cargo-b-c-1.0.0
+PackageVersion: 1.0.0
+PackageDownloadLocation: https://crates.io/api/v1/crates/b_c/1.0.0/download
+FilesAnalyzed: false
+PackageChecksum: SHA256: 0123
+PackageLicenseConcluded: NOASSERTION
+PackageLicenseDeclared: MIT OR Apache-2.0
+PackageCopyrightText: NOASSERTION
+ExternalRef: PACKAGE-MANAGER purl pkg:cargo/b_c@1.0.0
+
+Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-cargo-a-1.0.0
+Relationship: SPDXRef-Package-cargo-a-1.0.0 DEPENDS_ON SPDXRef-Package-cargo-b-c-1.0.0
+",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn document_json() {
+        let actual = serde_json::to_value(document(
+            "a",
+            &Version::new(1, 0, 0),
+            &input(),
+            "2020-10-01T00:00:00Z",
+        ))
+        .unwrap();
+
+        assert_eq!(actual["spdxVersion"], "SPDX-2.3");
+        assert_eq!(actual["SPDXID"], "SPDXRef-DOCUMENT");
+        assert_eq!(
+            actual["packages"][1],
+            serde_json::json!({
+                "SPDXID": "SPDXRef-Package-cargo-b-c-1.0.0",
+                "name": "b_c",
+                "versionInfo": "1.0.0",
+                "downloadLocation": "https://crates.io/api/v1/crates/b_c/1.0.0/download",
+                "filesAnalyzed": false,
+                "checksums": [{"algorithm": "SHA256", "checksumValue": "0123"}],
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": "MIT OR Apache-2.0",
+                "copyrightText": "NOASSERTION",
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": "pkg:cargo/b_c@1.0.0"
+                }]
+            })
+        );
+        assert_eq!(
+            actual["relationships"][1],
+            serde_json::json!({
+                "spdxElementId": "SPDXRef-Package-cargo-a-1.0.0",
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": "SPDXRef-Package-cargo-b-c-1.0.0"
+            })
+        );
+    }
+
+    #[test]
+    fn document_pre_release_without_metadata() {
+        let input = vec![node("a", "1.0.0-alpha.1+build.5", &[])];
+
+        let actual = tag_value(&document(
+            "a",
+            &Version::parse("1.0.0-alpha.1+build.5").unwrap(),
+            &input,
+            "2020-10-01T00:00:00Z",
+        ));
+
+        assert!(actual.contains("SPDXID: SPDXRef-Package-cargo-a-1.0.0-alpha.1-build.5\n"));
+        assert!(actual
+            .contains("ExternalRef: PACKAGE-MANAGER purl pkg:cargo/a@1.0.0-alpha.1%2Bbuild.5\n"));
+        assert!(actual.contains("PackageLicenseDeclared: NOASSERTION\n"));
+        assert!(!actual.contains("PackageChecksum"));
+    }
+}
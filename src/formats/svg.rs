@@ -0,0 +1,143 @@
+use crate::domain::Crate;
+use crate::formats::escape;
+use crate::formats::layout::{layout, NODE_HEIGHT};
+use semver::Version;
+use std::fmt::Write;
+
+/// Approximate width of a character of the 12px sans-serif labels.
+const CHAR_WIDTH: f64 = 7.0;
+const PADDING: f64 = 16.0;
+
+/// Renders the dependency graph of crate+version as a self-contained SVG image, laid out in
+/// layers from the root down. Hovering a crate shows its version, hovering an edge the version
+/// requirement it was resolved from. Optional edges are gray.
+pub(crate) fn graph(name: &str, version: &Version, crates: &[Crate]) -> String {
+    let layout = layout(name, version, crates, |c| {
+        let chars = c.name.chars().count().max(c.version.to_string().len() + 1);
+        chars as f64 * CHAR_WIDTH + PADDING
+    });
+
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="12">"#,
+        layout.width, layout.height
+    )
+    .unwrap();
+    writeln!(svg, "  <defs>").unwrap();
+    writeln!(
+        svg,
+        r#"    <marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z"/></marker>"#
+    )
+    .unwrap();
+    writeln!(svg, "  </defs>").unwrap();
+
+    for edge in &layout.edges {
+        let c = &crates[edge.from];
+        let d = &c.dependency[edge.dependency];
+
+        let mut title = format!("{} {} -> {} {}", c.name, c.version, d.name, d.version);
+        if let Some(req) = &d.req {
+            write!(title, "\nrequires {}", req).unwrap();
+        }
+        if d.optional {
+            title += "\noptional";
+        }
+
+        let stroke = if d.optional { "#999" } else { "#333" };
+
+        let points = edge
+            .points
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(
+            svg,
+            r#"  <g class="edge"><title>{}</title><polyline points="{}" fill="none" stroke="{}" marker-end="url(#arrow)"/></g>"#,
+            escape(&title),
+            points,
+            stroke
+        )
+        .unwrap();
+    }
+
+    for node in &layout.nodes {
+        let c = &crates[node.index];
+        let root = c.name == name && &c.version == version;
+        let center = node.x + node.width / 2.0;
+
+        writeln!(
+            svg,
+            r##"  <g class="node"><title>{} {}</title><rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="{}" stroke="#333" stroke-width="{}"/><text x="{}" y="{}" text-anchor="middle">{}</text><text x="{}" y="{}" text-anchor="middle" fill="#666">v{}</text></g>"##,
+            escape(&c.name),
+            c.version,
+            node.x,
+            node.y,
+            node.width,
+            NODE_HEIGHT,
+            if root { "#ddf" } else { "#fff" },
+            if root { 2 } else { 1 },
+            center,
+            node.y + 15.0,
+            escape(&c.name),
+            center,
+            node.y + 29.0,
+            escape(&c.version.to_string())
+        )
+        .unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn graph_svg() {
+        let mut input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+        ];
+        input[0].dependency[0].req = Some("^1.0".to_owned());
+
+        let actual = graph("a", &Version::new(1, 0, 0), &input);
+
+        let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" width="90" height="152" viewBox="0 0 90 152" font-family="sans-serif" font-size="12">
+  <defs>
+    <marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z"/></marker>
+  </defs>
+  <g class="edge"><title>a 1.0.0 -&gt; b 1.0.0
+requires ^1.0</title><polyline points="45,52 45,100" fill="none" stroke="#333" marker-end="url(#arrow)"/></g>
+  <g class="node"><title>a 1.0.0</title><rect x="16" y="16" width="58" height="36" rx="4" fill="#ddf" stroke="#333" stroke-width="2"/><text x="45" y="31" text-anchor="middle">a</text><text x="45" y="45" text-anchor="middle" fill="#666">v1.0.0</text></g>
+  <g class="node"><title>b 1.0.0</title><rect x="16" y="100" width="58" height="36" rx="4" fill="#fff" stroke="#333" stroke-width="1"/><text x="45" y="115" text-anchor="middle">b</text><text x="45" y="129" text-anchor="middle" fill="#666">v1.0.0</text></g>
+</svg>
+"##;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn graph_svg_optional_edge_escaped() {
+        let mut input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0+build")]),
+            node("b", "1.0.0+build", &[]),
+        ];
+        input[0].dependency[0].req = Some(">=1.0, <2.0".to_owned());
+        input[0].dependency[0].optional = true;
+
+        let actual = graph("a", &Version::new(1, 0, 0), &input);
+
+        assert!(actual.contains(
+            "<title>a 1.0.0 -&gt; b 1.0.0+build\nrequires &gt;=1.0, &lt;2.0\noptional</title>"
+        ));
+        assert!(actual.contains(r##"fill="none" stroke="#999" marker-end="url(#arrow)""##));
+    }
+}
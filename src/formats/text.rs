@@ -0,0 +1,112 @@
+use crate::formats::versions;
+use crate::graph::{EdgeChange, GraphDiff, NodeChange};
+use semver::Version;
+use std::fmt::Write;
+
+/// Renders a graph diff as a human readable, line oriented report.
+pub(crate) fn diff(name: &str, from: &Version, to: &Version, diff: &GraphDiff) -> String {
+    let mut text = String::new();
+
+    writeln!(text, "{} {} -> {}", name, from, to).unwrap();
+
+    writeln!(text).unwrap();
+    writeln!(text, "nodes:").unwrap();
+    for change in &diff.added_nodes {
+        writeln!(text, "+ {}", node(change)).unwrap();
+    }
+    for change in &diff.removed_nodes {
+        writeln!(text, "- {}", node(change)).unwrap();
+    }
+    for change in &diff.changed_nodes {
+        writeln!(text, "~ {}", node(change)).unwrap();
+    }
+
+    writeln!(text).unwrap();
+    writeln!(text, "edges:").unwrap();
+    for change in &diff.added_edges {
+        writeln!(text, "+ {}", edge(change)).unwrap();
+    }
+    for change in &diff.removed_edges {
+        writeln!(text, "- {}", edge(change)).unwrap();
+    }
+    for change in &diff.changed_edges {
+        writeln!(text, "~ {}", edge(change)).unwrap();
+    }
+
+    text
+}
+
+fn node(change: &NodeChange) -> String {
+    format!("{} {}", change.name, versions(&change.from, &change.to))
+}
+
+fn edge(change: &EdgeChange) -> String {
+    format!(
+        "{} -> {} {}",
+        change.parent,
+        change.child,
+        versions(&change.from, &change.to)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+    use crate::test_support::node;
+
+    #[test]
+    fn diff_report() {
+        let from = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+            node("c", "1.0.0", &[]),
+        ];
+
+        let to = vec![
+            node("a", "1.1.0", &[("b", "2.0.0"), ("d", "1.0.0")]),
+            node("b", "2.0.0", &[]),
+            node("d", "1.0.0", &[]),
+        ];
+
+        let actual = diff(
+            "a",
+            &Version::new(1, 0, 0),
+            &Version::new(1, 1, 0),
+            &graph::diff(&from, &to),
+        );
+
+        let expected = "a 1.0.0 -> 1.1.0
+
+nodes:
++ d 1.0.0
+- c 1.0.0
+~ a 1.0.0 -> 1.1.0
+~ b 1.0.0 -> 2.0.0
+
+edges:
++ a -> d 1.0.0
+- a -> c 1.0.0
+~ a -> b 1.0.0 -> 2.0.0
+";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        let graph = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+        ];
+
+        let actual = diff(
+            "a",
+            &Version::new(1, 0, 0),
+            &Version::new(1, 0, 0),
+            &graph::diff(&graph, &graph),
+        );
+
+        assert_eq!(actual, "a 1.0.0 -> 1.0.0\n\nnodes:\n\nedges:\n");
+    }
+}
@@ -0,0 +1,256 @@
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Prefix {
+    Indent,
+    Depth,
+    None,
+}
+
+impl Prefix {
+    /// Parses the prefix as named by `cargo tree --prefix`.
+    pub(crate) fn parse(prefix: &str) -> Option<Self> {
+        match prefix {
+            "indent" => Some(Prefix::Indent),
+            "depth" => Some(Prefix::Depth),
+            "none" => Some(Prefix::None),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TreeOptions {
+    /// Shows the crates depending on every version of the named crate instead.
+    pub(crate) invert: Option<String>,
+    /// Maximum display depth.
+    pub(crate) depth: Option<usize>,
+    pub(crate) prefix: Prefix,
+}
+
+type Key = (String, Version);
+
+/// Renders the dependency graph of crate+version as `cargo tree` does. Crates already shown are
+/// marked `(*)` and not expanded again.
+pub(crate) fn tree(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+    options: &TreeOptions,
+) -> String {
+    let mut edges: HashMap<Key, Vec<Key>> = crates
+        .iter()
+        .map(|c| ((c.name.to_owned(), c.version.to_owned()), Vec::new()))
+        .collect();
+
+    for c in crates {
+        for d in &c.dependency {
+            let parent = (c.name.to_owned(), c.version.to_owned());
+            let child = (d.name.to_owned(), d.version.to_owned());
+
+            // crates left out of the graph are not shown.
+            if !edges.contains_key(&child) {
+                continue;
+            }
+
+            let (from, to) = if options.invert.is_some() {
+                (child, parent)
+            } else {
+                (parent, child)
+            };
+
+            edges.get_mut(&from).unwrap().push(to);
+        }
+    }
+
+    for children in edges.values_mut() {
+        children.sort();
+    }
+
+    let mut roots = match &options.invert {
+        Some(invert) => edges
+            .keys()
+            .filter(|(name, _)| name == invert)
+            .cloned()
+            .collect::<Vec<_>>(),
+        None => vec![(name.to_owned(), version.to_owned())],
+    };
+    roots.sort();
+
+    let mut printer = Printer {
+        edges: &edges,
+        options,
+        text: String::new(),
+        levels_continue: Vec::new(),
+        visited: HashSet::new(),
+        print_stack: Vec::new(),
+    };
+
+    for (index, root) in roots.iter().enumerate() {
+        if index > 0 {
+            writeln!(printer.text).unwrap();
+        }
+        printer.node(root);
+    }
+
+    printer.text
+}
+
+struct Printer<'a> {
+    edges: &'a HashMap<Key, Vec<Key>>,
+    options: &'a TreeOptions,
+    text: String,
+    /// Whether every level being printed has more siblings to come.
+    levels_continue: Vec<bool>,
+    visited: HashSet<Key>,
+    print_stack: Vec<Key>,
+}
+
+impl<'a> Printer<'a> {
+    fn node(&mut self, key: &Key) {
+        match self.options.prefix {
+            Prefix::Indent => {
+                if let Some((last, rest)) = self.levels_continue.split_last() {
+                    for &continues in rest {
+                        self.text += if continues { "│   " } else { "    " };
+                    }
+                    self.text += if *last { "├── " } else { "└── " };
+                }
+            }
+            Prefix::Depth => write!(self.text, "{}", self.levels_continue.len()).unwrap(),
+            Prefix::None => {}
+        }
+
+        let in_cycle = self.print_stack.contains(key);
+        let new = self.visited.insert(key.clone());
+        let has_children = matches!(self.edges.get(key), Some(e) if !e.is_empty());
+        let star = if new || in_cycle || !has_children {
+            ""
+        } else {
+            " (*)"
+        };
+
+        writeln!(self.text, "{} v{}{}", key.0, key.1, star).unwrap();
+
+        if !new || in_cycle {
+            return;
+        }
+
+        self.print_stack.push(key.clone());
+        self.children(key);
+        self.print_stack.pop();
+    }
+
+    fn children(&mut self, key: &Key) {
+        if matches!(self.options.depth, Some(depth) if self.levels_continue.len() >= depth) {
+            return;
+        }
+
+        let edges = self.edges;
+        let mut children = edges.get(key).into_iter().flatten().peekable();
+        while let Some(child) = children.next() {
+            self.levels_continue.push(children.peek().is_some());
+            self.node(child);
+            self.levels_continue.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    fn input() -> Vec<Crate> {
+        vec![
+            node(
+                "a",
+                "1.0.0",
+                &[("b", "1.0.0"), ("c", "1.0.0"), ("cc", "1.0.0")],
+            ),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[("d", "1.0.0")]),
+            node("cc", "1.0.0", &[]),
+            node("d", "1.0.0", &[]),
+        ]
+    }
+
+    fn options(invert: Option<&str>, depth: Option<usize>, prefix: Prefix) -> TreeOptions {
+        TreeOptions {
+            invert: invert.map(str::to_owned),
+            depth,
+            prefix,
+        }
+    }
+
+    #[test]
+    fn tree_indent() {
+        let actual = tree(
+            "a",
+            &Version::new(1, 0, 0),
+            &input(),
+            &options(None, None, Prefix::Indent),
+        );
+
+        let expected = "a v1.0.0
+├── b v1.0.0
+│   └── c v1.0.0
+│       └── d v1.0.0
+├── c v1.0.0 (*)
+└── cc v1.0.0
+";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn tree_depth_prefix() {
+        let actual = tree(
+            "a",
+            &Version::new(1, 0, 0),
+            &input(),
+            &options(None, Some(1), Prefix::Depth),
+        );
+
+        assert_eq!(actual, "0a v1.0.0\n1b v1.0.0\n1c v1.0.0\n1cc v1.0.0\n");
+    }
+
+    #[test]
+    fn tree_invert() {
+        let actual = tree(
+            "a",
+            &Version::new(1, 0, 0),
+            &input(),
+            &options(Some("d"), None, Prefix::Indent),
+        );
+
+        let expected = "d v1.0.0
+└── c v1.0.0
+    ├── a v1.0.0
+    └── b v1.0.0
+        └── a v1.0.0
+";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn tree_cycle_and_missing_crate() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("excluded", "1.0.0")]),
+            node("b", "1.0.0", &[("a", "1.0.0")]),
+        ];
+
+        let actual = tree(
+            "a",
+            &Version::new(1, 0, 0),
+            &input,
+            &options(None, None, Prefix::None),
+        );
+
+        assert_eq!(actual, "a v1.0.0\nb v1.0.0\na v1.0.0\n");
+    }
+}
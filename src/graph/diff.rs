@@ -0,0 +1,198 @@
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct GraphDiff {
+    pub(crate) added_nodes: Vec<NodeChange>,
+    pub(crate) removed_nodes: Vec<NodeChange>,
+    pub(crate) changed_nodes: Vec<NodeChange>,
+    pub(crate) added_edges: Vec<EdgeChange>,
+    pub(crate) removed_edges: Vec<EdgeChange>,
+    pub(crate) changed_edges: Vec<EdgeChange>,
+}
+
+/// A crate name whose set of versions differs between the two graphs.
+#[derive(Debug, PartialEq)]
+pub(crate) struct NodeChange {
+    pub(crate) name: String,
+    pub(crate) from: Vec<Version>,
+    pub(crate) to: Vec<Version>,
+}
+
+/// A parent to child edge whose set of child versions differs between the two graphs.
+#[derive(Debug, PartialEq)]
+pub(crate) struct EdgeChange {
+    pub(crate) parent: String,
+    pub(crate) child: String,
+    pub(crate) from: Vec<Version>,
+    pub(crate) to: Vec<Version>,
+}
+
+/// Compares two graphs by crate name, so an upgraded crate shows up as changed rather than as an
+/// addition and a removal.
+pub(crate) fn diff(from: &[Crate], to: &[Crate]) -> GraphDiff {
+    let mut result = GraphDiff::default();
+
+    for (name, (from, to)) in pair(nodes(from), nodes(to)) {
+        let change = NodeChange { name, from, to };
+
+        if change.from.is_empty() {
+            result.added_nodes.push(change);
+        } else if change.to.is_empty() {
+            result.removed_nodes.push(change);
+        } else {
+            result.changed_nodes.push(change);
+        }
+    }
+
+    for ((parent, child), (from, to)) in pair(edges(from), edges(to)) {
+        let change = EdgeChange {
+            parent,
+            child,
+            from,
+            to,
+        };
+
+        if change.from.is_empty() {
+            result.added_edges.push(change);
+        } else if change.to.is_empty() {
+            result.removed_edges.push(change);
+        } else {
+            result.changed_edges.push(change);
+        }
+    }
+
+    result
+}
+
+fn nodes(crates: &[Crate]) -> BTreeMap<String, BTreeSet<Version>> {
+    let mut results: BTreeMap<String, BTreeSet<Version>> = BTreeMap::new();
+
+    for c in crates {
+        results
+            .entry(c.name.to_owned())
+            .or_default()
+            .insert(c.version.to_owned());
+    }
+
+    results
+}
+
+fn edges(crates: &[Crate]) -> BTreeMap<(String, String), BTreeSet<Version>> {
+    let mut results: BTreeMap<(String, String), BTreeSet<Version>> = BTreeMap::new();
+
+    for c in crates {
+        for d in &c.dependency {
+            results
+                .entry((c.name.to_owned(), d.name.to_owned()))
+                .or_default()
+                .insert(d.version.to_owned());
+        }
+    }
+
+    results
+}
+
+/// Pairs up the versions on both sides of every key, dropping keys whose versions are unchanged.
+fn pair<K: Ord>(
+    from: BTreeMap<K, BTreeSet<Version>>,
+    mut to: BTreeMap<K, BTreeSet<Version>>,
+) -> BTreeMap<K, (Vec<Version>, Vec<Version>)> {
+    let mut results = BTreeMap::new();
+
+    for (key, from_versions) in from {
+        let to_versions = to.remove(&key).unwrap_or_default();
+
+        if from_versions != to_versions {
+            results.insert(
+                key,
+                (
+                    from_versions.into_iter().collect(),
+                    to_versions.into_iter().collect(),
+                ),
+            );
+        }
+    }
+
+    for (key, to_versions) in to {
+        results.insert(key, (Vec::new(), to_versions.into_iter().collect()));
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn diff_nodes_and_edges() {
+        let from = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+            node("c", "1.0.0", &[]),
+        ];
+
+        let to = vec![
+            node("a", "1.1.0", &[("b", "2.0.0"), ("d", "1.0.0")]),
+            node("b", "2.0.0", &[]),
+            node("d", "1.0.0", &[]),
+        ];
+
+        let actual = diff(&from, &to);
+
+        assert_eq!(
+            actual.added_nodes,
+            vec![NodeChange {
+                name: "d".to_owned(),
+                from: vec![],
+                to: vec![Version::new(1, 0, 0)],
+            }]
+        );
+        assert_eq!(
+            actual.removed_nodes,
+            vec![NodeChange {
+                name: "c".to_owned(),
+                from: vec![Version::new(1, 0, 0)],
+                to: vec![],
+            }]
+        );
+        assert_eq!(
+            actual
+                .changed_nodes
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            actual.added_edges,
+            vec![EdgeChange {
+                parent: "a".to_owned(),
+                child: "d".to_owned(),
+                from: vec![],
+                to: vec![Version::new(1, 0, 0)],
+            }]
+        );
+        assert_eq!(
+            actual.removed_edges,
+            vec![EdgeChange {
+                parent: "a".to_owned(),
+                child: "c".to_owned(),
+                from: vec![Version::new(1, 0, 0)],
+                to: vec![],
+            }]
+        );
+        assert_eq!(
+            actual.changed_edges,
+            vec![EdgeChange {
+                parent: "a".to_owned(),
+                child: "b".to_owned(),
+                from: vec![Version::new(1, 0, 0)],
+                to: vec![Version::new(2, 0, 0)],
+            }]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Duplicate {
+    pub(crate) name: String,
+    pub(crate) versions: Vec<DuplicateVersion>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct DuplicateVersion {
+    pub(crate) version: Version,
+    pub(crate) parents: Vec<(String, Version)>,
+}
+
+type Parents = Vec<(String, Version)>;
+
+/// Groups crates by name, reporting every name present in more than one version along with the
+/// parents that pull in each version.
+pub(crate) fn duplicates(crates: &[Crate]) -> Vec<Duplicate> {
+    let mut groups: BTreeMap<String, BTreeMap<Version, Parents>> = BTreeMap::new();
+
+    for c in crates {
+        groups
+            .entry(c.name.to_owned())
+            .or_default()
+            .entry(c.version.to_owned())
+            .or_default();
+    }
+
+    for c in crates {
+        for d in &c.dependency {
+            if let Some(parents) = groups
+                .get_mut(&d.name)
+                .and_then(|versions| versions.get_mut(&d.version))
+            {
+                parents.push((c.name.to_owned(), c.version.to_owned()));
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(name, versions)| Duplicate {
+            name,
+            versions: versions
+                .into_iter()
+                .map(|(version, mut parents)| {
+                    parents.sort();
+                    DuplicateVersion { version, parents }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn duplicates_grouped_by_name() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("rand", "0.7.3")]),
+            node("b", "1.0.0", &[("rand", "0.8.0")]),
+            node("rand", "0.7.3", &[]),
+            node("rand", "0.8.0", &[]),
+        ];
+
+        let expected = vec![Duplicate {
+            name: "rand".to_owned(),
+            versions: vec![
+                DuplicateVersion {
+                    version: Version::new(0, 7, 3),
+                    parents: vec![("a".to_owned(), Version::new(1, 0, 0))],
+                },
+                DuplicateVersion {
+                    version: Version::new(0, 8, 0),
+                    parents: vec![("b".to_owned(), Version::new(1, 0, 0))],
+                },
+            ],
+        }];
+
+        assert_eq!(duplicates(&input), expected);
+    }
+}
@@ -0,0 +1,251 @@
+mod diff;
+mod duplicates;
+mod order;
+mod rules;
+mod stats;
+
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::{HashMap, HashSet};
+
+pub(crate) use diff::{diff, EdgeChange, GraphDiff, NodeChange};
+pub(crate) use duplicates::{duplicates, Duplicate};
+pub(crate) use order::{build_order, Cycle};
+pub(crate) use rules::{restrict, Rules};
+pub(crate) use stats::{stats, Degree, Stats};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct NodeAnnotation {
+    pub(crate) depth: usize,
+    pub(crate) dependents: usize,
+}
+
+/// Annotates every crate with its minimum distance from the root and its number of incoming edges.
+pub(crate) fn annotate(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+) -> HashMap<(String, Version), NodeAnnotation> {
+    annotate_from(&[(name.to_owned(), version.to_owned())], crates)
+}
+
+/// Annotates every crate with its minimum distance from any of the roots and its number of
+/// incoming edges.
+pub(crate) fn annotate_from(
+    roots: &[(String, Version)],
+    crates: &[Crate],
+) -> HashMap<(String, Version), NodeAnnotation> {
+    let depths = depths_from(roots, crates);
+
+    let mut results = crates
+        .iter()
+        .map(|c| {
+            let key = (c.name.to_owned(), c.version.to_owned());
+            let depth = depths.get(&key).copied().unwrap_or_default();
+            (
+                key,
+                NodeAnnotation {
+                    depth,
+                    dependents: 0,
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    for c in crates {
+        for d in &c.dependency {
+            if let Some(annotation) = results.get_mut(&(d.name.to_owned(), d.version.to_owned())) {
+                annotation.dependents += 1;
+            }
+        }
+    }
+
+    results
+}
+
+/// Keeps only the crates within `depth` edges of the root.
+pub(crate) fn limit_depth(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+    depth: usize,
+) -> Vec<Crate> {
+    let depths = depths(name, version, crates);
+
+    crates
+        .iter()
+        .filter(|c| {
+            matches!(
+                depths.get(&(c.name.to_owned(), c.version.to_owned())),
+                Some(&d) if d <= depth
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+/// Keeps only the crates reachable from the root.
+pub(crate) fn reachable(name: &str, version: &Version, crates: &[Crate]) -> Vec<Crate> {
+    let depths = depths(name, version, crates);
+
+    crates
+        .iter()
+        .filter(|c| depths.contains_key(&(c.name.to_owned(), c.version.to_owned())))
+        .cloned()
+        .collect()
+}
+
+/// Unions many graphs, keeping each crate once.
+pub(crate) fn merge(graphs: &[Vec<Crate>]) -> Vec<Crate> {
+    let mut results = graphs.iter().flatten().cloned().collect::<Vec<_>>();
+
+    results.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    results.dedup_by(|a, b| a.name == b.name && a.version == b.version);
+
+    results
+}
+
+fn depths(name: &str, version: &Version, crates: &[Crate]) -> HashMap<(String, Version), usize> {
+    depths_from(&[(name.to_owned(), version.to_owned())], crates)
+}
+
+/// Breadth first search from the roots, recording the minimum distance to every reachable crate.
+fn depths_from(roots: &[(String, Version)], crates: &[Crate]) -> HashMap<(String, Version), usize> {
+    let index = crates
+        .iter()
+        .map(|c| ((c.name.to_owned(), c.version.to_owned()), c))
+        .collect::<HashMap<_, _>>();
+
+    let mut results = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut layer = roots.to_vec();
+    let mut depth = 0;
+
+    while !layer.is_empty() {
+        let mut next = Vec::new();
+
+        for key in layer {
+            if !visited.insert(key.clone()) {
+                continue;
+            }
+
+            if let Some(c) = index.get(&key) {
+                for d in &c.dependency {
+                    next.push((d.name.to_owned(), d.version.to_owned()));
+                }
+            }
+
+            results.insert(key, depth);
+        }
+
+        layer = next;
+        depth += 1;
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    fn graph() -> Vec<Crate> {
+        vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[("d", "1.0.0")]),
+            node("d", "1.0.0", &[]),
+        ]
+    }
+
+    #[test]
+    fn annotate_depth_and_dependents() {
+        let actual = annotate("a", &Version::new(1, 0, 0), &graph());
+
+        let get = |name: &str| {
+            actual
+                .get(&(name.to_owned(), Version::new(1, 0, 0)))
+                .cloned()
+                .unwrap()
+        };
+
+        assert_eq!(
+            get("a"),
+            NodeAnnotation {
+                depth: 0,
+                dependents: 0
+            }
+        );
+        assert_eq!(
+            get("b"),
+            NodeAnnotation {
+                depth: 1,
+                dependents: 1
+            }
+        );
+        assert_eq!(
+            get("c"),
+            NodeAnnotation {
+                depth: 1,
+                dependents: 2
+            }
+        );
+        assert_eq!(
+            get("d"),
+            NodeAnnotation {
+                depth: 2,
+                dependents: 1
+            }
+        );
+    }
+
+    #[test]
+    fn annotate_from_nearest_root() {
+        let roots = vec![
+            ("a".to_owned(), Version::new(1, 0, 0)),
+            ("c".to_owned(), Version::new(1, 0, 0)),
+        ];
+
+        let actual = annotate_from(&roots, &graph());
+
+        assert_eq!(
+            actual
+                .get(&("d".to_owned(), Version::new(1, 0, 0)))
+                .map(|a| a.depth),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn reachable_from_root() {
+        let actual = reachable("b", &Version::new(1, 0, 0), &graph())
+            .into_iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec!["b", "c", "d"]);
+    }
+
+    #[test]
+    fn merge_keeps_each_crate_once() {
+        let graphs = vec![graph(), reachable("c", &Version::new(1, 0, 0), &graph())];
+
+        let actual = merge(&graphs)
+            .into_iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn limit_depth_keeps_nearest_layers() {
+        let actual = limit_depth("a", &Version::new(1, 0, 0), &graph(), 1)
+            .into_iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec!["a", "b", "c"]);
+    }
+}
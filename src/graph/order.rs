@@ -0,0 +1,149 @@
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Cycle {
+    /// The crates of the cycle in dependency order, the first crate depending on the second.
+    pub(crate) crates: Vec<(String, Version)>,
+}
+
+impl Cycle {
+    pub(crate) fn describe(&self) -> String {
+        let mut crates = self
+            .crates
+            .iter()
+            .map(|(name, version)| format!("{} {}", name, version))
+            .collect::<Vec<_>>();
+
+        if let Some(first) = crates.first().cloned() {
+            crates.push(first);
+        }
+
+        format!("dependency cycle: {}", crates.join(" -> "))
+    }
+}
+
+/// Orders the graph leaves first, grouping into levels the crates that can be built in parallel
+/// once every previous level is built. Dependencies missing from the graph are ignored.
+pub(crate) fn build_order(crates: &[Crate]) -> Result<Vec<Vec<(String, Version)>>, Cycle> {
+    let keys = crates
+        .iter()
+        .map(|c| (c.name.to_owned(), c.version.to_owned()))
+        .collect::<HashSet<_>>();
+
+    let mut remaining = crates
+        .iter()
+        .map(|c| {
+            let dependency = c
+                .dependency
+                .iter()
+                .map(|d| (d.name.to_owned(), d.version.to_owned()))
+                .filter(|key| keys.contains(key))
+                .collect::<HashSet<_>>();
+
+            ((c.name.to_owned(), c.version.to_owned()), dependency)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut results = Vec::new();
+
+    while !remaining.is_empty() {
+        let mut level = remaining
+            .iter()
+            .filter(|(_, dependency)| dependency.is_empty())
+            .map(|(key, _)| key.to_owned())
+            .collect::<Vec<_>>();
+
+        if level.is_empty() {
+            return Err(cycle(&remaining));
+        }
+
+        level.sort();
+
+        for key in &level {
+            remaining.remove(key);
+        }
+
+        for dependency in remaining.values_mut() {
+            for key in &level {
+                dependency.remove(key);
+            }
+        }
+
+        results.push(level);
+    }
+
+    Ok(results)
+}
+
+/// Follows dependencies from the smallest remaining crate until one repeats. Every remaining
+/// crate has a remaining dependency, so the walk always ends on a cycle.
+fn cycle(remaining: &HashMap<(String, Version), HashSet<(String, Version)>>) -> Cycle {
+    let mut path: Vec<(String, Version)> = Vec::new();
+    let mut current = remaining.keys().min().cloned();
+
+    while let Some(key) = current {
+        if let Some(position) = path.iter().position(|k| k == &key) {
+            return Cycle {
+                crates: path.split_off(position),
+            };
+        }
+
+        current = remaining
+            .get(&key)
+            .and_then(|dependency| dependency.iter().min().cloned());
+        path.push(key);
+    }
+
+    Cycle { crates: path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    fn key(name: &str) -> (String, Version) {
+        (name.to_owned(), Version::new(1, 0, 0))
+    }
+
+    #[test]
+    fn build_order_levels() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[("d", "1.0.0"), ("missing", "1.0.0")]),
+            node("d", "1.0.0", &[]),
+            node("e", "1.0.0", &[]),
+        ];
+
+        assert_eq!(
+            build_order(&input),
+            Ok(vec![
+                vec![key("d"), key("e")],
+                vec![key("c")],
+                vec![key("b")],
+                vec![key("a")],
+            ])
+        );
+    }
+
+    #[test]
+    fn build_order_cycle() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[("b", "1.0.0"), ("d", "1.0.0")]),
+            node("d", "1.0.0", &[]),
+        ];
+
+        let actual = build_order(&input).unwrap_err();
+
+        assert_eq!(actual.crates, vec![key("b"), key("c")]);
+        assert_eq!(
+            actual.describe(),
+            "dependency cycle: b 1.0.0 -> c 1.0.0 -> b 1.0.0"
+        );
+    }
+}
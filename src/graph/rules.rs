@@ -0,0 +1,157 @@
+use crate::domain::Crate;
+use semver::Version;
+use std::collections::{HashMap, HashSet};
+
+/// Crate name patterns restricting a traversal, `*` matching any run of characters and `?` any
+/// single character.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Rules {
+    exclude: Vec<String>,
+    prune: Vec<String>,
+}
+
+impl Rules {
+    pub(crate) fn new(exclude: Vec<String>, prune: Vec<String>) -> Self {
+        Self { exclude, prune }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.exclude.is_empty() && self.prune.is_empty()
+    }
+
+    /// Whether the crate, along with its dependencies, is left out of the graph.
+    pub(crate) fn excludes(&self, name: &str) -> bool {
+        self.exclude.iter().any(|pattern| glob(pattern, name))
+    }
+
+    /// Whether the crate is kept in the graph as a leaf.
+    pub(crate) fn prunes(&self, name: &str) -> bool {
+        self.prune.iter().any(|pattern| glob(pattern, name))
+    }
+
+    /// The crate as kept in the graph: without dependencies when pruned, without its excluded
+    /// dependencies otherwise.
+    pub(crate) fn apply(&self, c: &Crate) -> Crate {
+        let mut c = c.clone();
+
+        if self.prunes(&c.name) {
+            c.dependency.clear();
+        } else {
+            c.dependency.retain(|d| !self.excludes(&d.name));
+        }
+
+        c
+    }
+}
+
+/// Keeps only the crates reachable from the root without going through an excluded crate or
+/// below a pruned one. Pruned crates lose their dependencies, the root is never excluded.
+pub(crate) fn restrict(
+    name: &str,
+    version: &Version,
+    crates: &[Crate],
+    rules: &Rules,
+) -> Vec<Crate> {
+    let index = crates
+        .iter()
+        .map(|c| ((c.name.to_owned(), c.version.to_owned()), c))
+        .collect::<HashMap<_, _>>();
+
+    let mut results = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![(name.to_owned(), version.to_owned())];
+
+    while let Some(key) = stack.pop() {
+        if !visited.insert(key.clone()) {
+            continue;
+        }
+
+        if let Some(&c) = index.get(&key) {
+            let c = rules.apply(c);
+
+            for d in &c.dependency {
+                stack.push((d.name.to_owned(), d.version.to_owned()));
+            }
+
+            results.push(c);
+        }
+    }
+
+    results.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    results
+}
+
+/// Matches the whole of `name` against `pattern`.
+fn glob(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    let (mut p, mut n) = (0, 0);
+    // the last `*` seen and the position in `name` it currently stands for.
+    let mut star = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob("winapi", "winapi"));
+        assert!(!glob("winapi", "winapi-util"));
+        assert!(glob("winapi*", "winapi-util"));
+        assert!(glob("*-sys", "openssl-sys"));
+        assert!(glob("serde_?", "serde_1"));
+        assert!(!glob("serde_?", "serde_json"));
+        assert!(glob("*", ""));
+    }
+
+    #[test]
+    fn restrict_excludes_and_prunes() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("winapi", "0.3.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0")]),
+            node("c", "1.0.0", &[("d", "1.0.0")]),
+            node("d", "1.0.0", &[]),
+            node("winapi", "0.3.0", &[("winapi-x86", "0.4.0")]),
+            node("winapi-x86", "0.4.0", &[]),
+        ];
+
+        let rules = Rules::new(vec!["winapi*".to_owned()], vec!["b".to_owned()]);
+
+        let actual = restrict("a", &Version::new(1, 0, 0), &input, &rules);
+
+        assert_eq!(
+            actual,
+            vec![
+                node("a", "1.0.0", &[("b", "1.0.0")]),
+                node("b", "1.0.0", &[]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::domain::Crate;
+use crate::graph::annotate;
+use semver::Version;
+use std::collections::{BTreeMap, HashSet};
+
+const TOP: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Stats {
+    pub(crate) nodes: usize,
+    pub(crate) edges: usize,
+    pub(crate) max_depth: usize,
+    pub(crate) mean_depth: f64,
+    pub(crate) widest_layer: Layer,
+    pub(crate) top_fan_in: Vec<Degree>,
+    pub(crate) top_fan_out: Vec<Degree>,
+    pub(crate) distinct_names: usize,
+    pub(crate) distinct_versions: usize,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Layer {
+    pub(crate) depth: usize,
+    pub(crate) crates: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Degree {
+    pub(crate) name: String,
+    pub(crate) version: Version,
+    pub(crate) count: usize,
+}
+
+/// Summarises the shape of the dependency graph of crate+version.
+pub(crate) fn stats(name: &str, version: &Version, crates: &[Crate]) -> Stats {
+    let annotations = annotate(name, version, crates);
+
+    let mut layers: BTreeMap<usize, usize> = BTreeMap::new();
+    for annotation in annotations.values() {
+        *layers.entry(annotation.depth).or_default() += 1;
+    }
+
+    let widest_layer = layers
+        .iter()
+        .fold(Layer::default(), |widest, (&depth, &crates)| {
+            if crates > widest.crates {
+                Layer { depth, crates }
+            } else {
+                widest
+            }
+        });
+
+    let fan_in = annotations
+        .iter()
+        .map(|((name, version), annotation)| Degree {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            count: annotation.dependents,
+        })
+        .collect::<Vec<_>>();
+
+    let fan_out = crates
+        .iter()
+        .map(|c| Degree {
+            name: c.name.to_owned(),
+            version: c.version.to_owned(),
+            count: c.dependency.len(),
+        })
+        .collect::<Vec<_>>();
+
+    Stats {
+        nodes: crates.len(),
+        edges: crates.iter().map(|c| c.dependency.len()).sum(),
+        max_depth: layers.keys().next_back().copied().unwrap_or_default(),
+        mean_depth: if annotations.is_empty() {
+            0.0
+        } else {
+            annotations.values().map(|a| a.depth).sum::<usize>() as f64 / annotations.len() as f64
+        },
+        widest_layer,
+        top_fan_in: top(fan_in),
+        top_fan_out: top(fan_out),
+        distinct_names: crates
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<HashSet<_>>()
+            .len(),
+        distinct_versions: crates
+            .iter()
+            .map(|c| (c.name.as_str(), &c.version))
+            .collect::<HashSet<_>>()
+            .len(),
+    }
+}
+
+/// Highest counts first, ties broken by name and version.
+fn top(mut degrees: Vec<Degree>) -> Vec<Degree> {
+    degrees.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| (&a.name, &a.version).cmp(&(&b.name, &b.version)))
+    });
+
+    degrees
+        .into_iter()
+        .filter(|d| d.count > 0)
+        .take(TOP)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    #[test]
+    fn stats_summary() {
+        let input = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "1.0.0"), ("d", "2.0.0")]),
+            node("c", "1.0.0", &[("d", "1.0.0")]),
+            node("d", "1.0.0", &[]),
+            node("d", "2.0.0", &[]),
+        ];
+
+        let actual = stats("a", &Version::new(1, 0, 0), &input);
+
+        assert_eq!(actual.nodes, 5);
+        assert_eq!(actual.edges, 5);
+        assert_eq!(actual.max_depth, 2);
+        assert!((actual.mean_depth - 1.2).abs() < f64::EPSILON);
+        assert_eq!(
+            actual.widest_layer,
+            Layer {
+                depth: 1,
+                crates: 2
+            }
+        );
+        assert_eq!(
+            actual.top_fan_in.first(),
+            Some(&Degree {
+                name: "c".to_owned(),
+                version: Version::new(1, 0, 0),
+                count: 2
+            })
+        );
+        assert_eq!(actual.top_fan_in.len(), 4);
+        assert_eq!(
+            actual
+                .top_fan_out
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(actual.distinct_names, 4);
+        assert_eq!(actual.distinct_versions, 5);
+    }
+}
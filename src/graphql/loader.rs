@@ -0,0 +1,74 @@
+use crate::domain::{Crate, CrateMetadata};
+use crate::graphql::Pools;
+use async_graphql::dataloader::Loader;
+use semver::Version;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Looks crates up by name and version, many at once.
+#[async_trait::async_trait]
+pub(crate) trait CrateSource: Send + Sync {
+    async fn get_crates(
+        &self,
+        keys: &[(String, Version)],
+    ) -> Result<HashMap<(String, Version), Crate>, String>;
+}
+
+#[async_trait::async_trait]
+impl CrateSource for Pools {
+    async fn get_crates(
+        &self,
+        keys: &[(String, Version)],
+    ) -> Result<HashMap<(String, Version), Crate>, String> {
+        self.data().get_crates(keys).await
+    }
+}
+
+/// Batches the crate lookups of a query, so resolving the dependencies of many crates is one
+/// database query per batch instead of one per crate.
+pub(crate) struct CrateLoader {
+    source: Arc<dyn CrateSource>,
+}
+
+impl CrateLoader {
+    pub(crate) fn new(source: Arc<dyn CrateSource>) -> Self {
+        Self { source }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<(String, Version)> for CrateLoader {
+    type Value = Crate;
+    type Error = String;
+
+    async fn load(
+        &self,
+        keys: &[(String, Version)],
+    ) -> Result<HashMap<(String, Version), Crate>, String> {
+        self.source.get_crates(keys).await
+    }
+}
+
+/// Batches the metadata lookups of a query, metadata only being looked up when asked for.
+pub(crate) struct MetadataLoader {
+    pools: Arc<Pools>,
+}
+
+impl MetadataLoader {
+    pub(crate) fn new(pools: Arc<Pools>) -> Self {
+        Self { pools }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<(String, Version)> for MetadataLoader {
+    type Value = CrateMetadata;
+    type Error = String;
+
+    async fn load(
+        &self,
+        keys: &[(String, Version)],
+    ) -> Result<HashMap<(String, Version), CrateMetadata>, String> {
+        self.pools.data().get_metadata(keys).await
+    }
+}
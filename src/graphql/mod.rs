@@ -0,0 +1,177 @@
+mod loader;
+mod types;
+
+use crate::cache::{CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
+use crate::data::Data;
+use crate::graphql::loader::{CrateLoader, MetadataLoader};
+use crate::graphql::types::Query;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{EmptyMutation, EmptySubscription, Schema, SchemaBuilder};
+use std::sync::Arc;
+
+/// Nesting allowed in a query.
+const MAX_DEPTH: usize = 16;
+
+/// Complexity allowed in a query, bounding the work a single request can ask for: a graph costs
+/// 100 and every field selected on its crates counts ten times, so graphs cannot be nested.
+const MAX_COMPLEXITY: usize = 1000;
+
+pub(crate) type CrateSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Pools shared by every resolver, owned so the schema can outlive a request.
+pub(crate) struct Pools {
+    database_pool: sqlx::MySqlPool,
+    http_client_pool: reqwest::Client,
+    redis_pool: redis::aio::MultiplexedConnection,
+    limiter: Arc<Limiter>,
+    memory_cache: Arc<MemoryCache>,
+    cache_config: Arc<CacheConfig>,
+}
+
+impl Pools {
+    fn data(&self) -> Data<'_> {
+        Data::new(
+            &self.database_pool,
+            &self.http_client_pool,
+            &self.redis_pool,
+            &self.limiter,
+            &self.memory_cache,
+            &self.cache_config,
+        )
+    }
+}
+
+pub(crate) fn schema(
+    database_pool: sqlx::MySqlPool,
+    http_client_pool: reqwest::Client,
+    redis_pool: redis::aio::MultiplexedConnection,
+    limiter: Arc<Limiter>,
+    memory_cache: Arc<MemoryCache>,
+    cache_config: Arc<CacheConfig>,
+) -> CrateSchema {
+    let pools = Arc::new(Pools {
+        database_pool,
+        http_client_pool,
+        redis_pool,
+        limiter,
+        memory_cache,
+        cache_config,
+    });
+
+    builder()
+        .data(DataLoader::new(CrateLoader::new(pools.clone())))
+        .data(DataLoader::new(MetadataLoader::new(pools.clone())))
+        .data(pools)
+        .finish()
+}
+
+/// Schema without its data, limiting every query.
+fn builder() -> SchemaBuilder<Query, EmptyMutation, EmptySubscription> {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Crate;
+    use crate::graphql::loader::CrateSource;
+    use crate::test_support::node;
+    use semver::Version;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Crates looked up from memory, recording the keys of every lookup.
+    struct RecordingSource {
+        crates: Vec<Crate>,
+        lookups: Mutex<Vec<Vec<(String, Version)>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CrateSource for RecordingSource {
+        async fn get_crates(
+            &self,
+            keys: &[(String, Version)],
+        ) -> Result<HashMap<(String, Version), Crate>, String> {
+            let mut keys = keys.to_vec();
+            keys.sort();
+            self.lookups.lock().unwrap().push(keys.clone());
+
+            Ok(self
+                .crates
+                .iter()
+                .filter(|c| keys.contains(&(c.name.to_owned(), c.version.to_owned())))
+                .map(|c| ((c.name.to_owned(), c.version.to_owned()), c.clone()))
+                .collect())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn resolved_dependencies_are_batched() {
+        let source = Arc::new(RecordingSource {
+            crates: vec![
+                node(
+                    "a",
+                    "1.0.0",
+                    &[("b", "1.0.0"), ("c", "1.0.0"), ("d", "1.0.0")],
+                ),
+                node("b", "1.0.0", &[]),
+                node("c", "1.0.0", &[]),
+                node("d", "1.0.0", &[]),
+            ],
+            lookups: Mutex::new(Vec::new()),
+        });
+        let schema = builder()
+            .data(DataLoader::new(CrateLoader::new(source.clone())))
+            .finish();
+
+        let response = schema
+            .execute(
+                r#"{ version(name: "a", version: "1.0.0") { dependencies { resolved { name } } } }"#,
+            )
+            .await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let key = |name: &str| (name.to_owned(), Version::new(1, 0, 0));
+        assert_eq!(
+            *source.lookups.lock().unwrap(),
+            vec![vec![key("a")], vec![key("b"), key("c"), key("d")]]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn nested_graphs_are_too_complex() {
+        let schema = builder().finish();
+
+        let response = schema
+            .execute(
+                r#"{ graph(name: "a", version: "1.0.0") { crates { graph { crates { name } } } } }"#,
+            )
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("too complex"));
+
+        let response = schema.execute(r#"{ crate(name: "a") { name } }"#).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+    }
+
+    #[test]
+    fn schema_types() {
+        let sdl = Schema::new(Query, EmptyMutation, EmptySubscription).sdl();
+
+        for expected in &[
+            "type Crate {",
+            "type Version {",
+            "type Dependency {",
+            "type Graph {",
+            "enum DependencyKind {",
+            "crate(name: String!): Crate!",
+        ] {
+            assert!(sdl.contains(expected), "expected {:?} in {}", expected, sdl);
+        }
+    }
+}
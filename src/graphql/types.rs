@@ -0,0 +1,280 @@
+use crate::domain::{Crate, CrateDependency, CrateMetadata};
+use crate::graph::Rules;
+use crate::graphql::loader::{CrateLoader, MetadataLoader};
+use crate::graphql::Pools;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Enum, Error, Object, Result};
+use semver::Version;
+use std::sync::Arc;
+
+pub(crate) struct Query;
+
+#[Object]
+impl Query {
+    /// A crate by name.
+    #[graphql(name = "crate")]
+    async fn krate(&self, name: String) -> CrateObject {
+        CrateObject { name }
+    }
+
+    /// A crate at a version, resolved from upstream when not known yet.
+    async fn version(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        version: String,
+    ) -> Result<Option<VersionObject>> {
+        load(ctx, name, parse_version(&version)?).await
+    }
+
+    /// The dependency graph of a crate at a version.
+    #[graphql(complexity = "100 + child_complexity")]
+    async fn graph(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        version: String,
+        depth: Option<usize>,
+        exclude: Option<Vec<String>>,
+        prune: Option<Vec<String>>,
+    ) -> Result<GraphObject> {
+        graph(ctx, name, parse_version(&version)?, depth, exclude, prune).await
+    }
+}
+
+pub(crate) struct CrateObject {
+    name: String,
+}
+
+#[Object(name = "Crate")]
+impl CrateObject {
+    async fn name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    /// Every published version, oldest first.
+    #[graphql(complexity = 100)]
+    async fn versions(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let pools = ctx.data_unchecked::<Arc<Pools>>();
+
+        Ok(pools
+            .data()
+            .get_versions(&self.name)
+            .await
+            .map_err(Error::new)?
+            .iter()
+            .map(Version::to_string)
+            .collect())
+    }
+
+    async fn version(&self, ctx: &Context<'_>, version: String) -> Result<Option<VersionObject>> {
+        load(ctx, self.name.to_owned(), parse_version(&version)?).await
+    }
+}
+
+pub(crate) struct VersionObject(Crate);
+
+#[Object(name = "Version")]
+impl VersionObject {
+    async fn name(&self) -> String {
+        self.0.name.to_owned()
+    }
+
+    async fn version(&self) -> String {
+        self.0.version.to_string()
+    }
+
+    /// SPDX license expression.
+    async fn license(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        Ok(metadata(ctx, &self.0).await?.license)
+    }
+
+    /// SHA-256 of the `.crate` file, hex encoded.
+    async fn checksum(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        Ok(metadata(ctx, &self.0).await?.checksum)
+    }
+
+    #[graphql(name = "crate")]
+    async fn krate(&self) -> CrateObject {
+        CrateObject {
+            name: self.0.name.to_owned(),
+        }
+    }
+
+    /// Normal dependencies, build and dev dependencies not being resolved.
+    async fn dependencies(&self) -> Vec<DependencyObject> {
+        self.0
+            .dependency
+            .iter()
+            .cloned()
+            .map(DependencyObject)
+            .collect()
+    }
+
+    /// The dependency graph of this crate.
+    #[graphql(complexity = "100 + child_complexity")]
+    async fn graph(
+        &self,
+        ctx: &Context<'_>,
+        depth: Option<usize>,
+        exclude: Option<Vec<String>>,
+        prune: Option<Vec<String>>,
+    ) -> Result<GraphObject> {
+        graph(
+            ctx,
+            self.0.name.to_owned(),
+            self.0.version.to_owned(),
+            depth,
+            exclude,
+            prune,
+        )
+        .await
+    }
+}
+
+pub(crate) struct DependencyObject(CrateDependency);
+
+#[Object(name = "Dependency")]
+impl DependencyObject {
+    async fn name(&self) -> String {
+        self.0.name.to_owned()
+    }
+
+    /// Version the requirement was resolved to.
+    async fn version(&self) -> String {
+        self.0.version.to_string()
+    }
+
+    /// Version requirement as declared.
+    async fn req(&self) -> Option<String> {
+        self.0.req.to_owned()
+    }
+
+    /// Always `NORMAL`, build and dev dependencies not being resolved.
+    async fn kind(&self) -> DependencyKindObject {
+        DependencyKindObject::Normal
+    }
+
+    async fn optional(&self) -> bool {
+        self.0.optional
+    }
+
+    /// The dependency at the version it was resolved to.
+    async fn resolved(&self, ctx: &Context<'_>) -> Result<Option<VersionObject>> {
+        load(ctx, self.0.name.to_owned(), self.0.version.to_owned()).await
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "DependencyKind")]
+pub(crate) enum DependencyKindObject {
+    Normal,
+}
+
+pub(crate) struct GraphObject {
+    name: String,
+    version: Version,
+    crates: Vec<Crate>,
+}
+
+#[Object(name = "Graph")]
+impl GraphObject {
+    async fn root(&self) -> Option<VersionObject> {
+        self.find(&self.name, &self.version)
+    }
+
+    /// Every crate of the graph, the root included.
+    #[graphql(complexity = "10 * child_complexity")]
+    async fn crates(&self) -> Vec<VersionObject> {
+        self.crates.iter().cloned().map(VersionObject).collect()
+    }
+
+    /// The crates of the graph depending on a crate at a version.
+    #[graphql(complexity = "10 * child_complexity")]
+    async fn dependents(&self, name: String, version: String) -> Result<Vec<VersionObject>> {
+        let version = parse_version(&version)?;
+
+        Ok(self
+            .crates
+            .iter()
+            .filter(|c| {
+                c.dependency
+                    .iter()
+                    .any(|d| d.name == name && d.version == version)
+            })
+            .cloned()
+            .map(VersionObject)
+            .collect())
+    }
+
+    /// A crate of the graph.
+    async fn version(&self, name: String, version: String) -> Result<Option<VersionObject>> {
+        Ok(self.find(&name, &parse_version(&version)?))
+    }
+}
+
+impl GraphObject {
+    fn find(&self, name: &str, version: &Version) -> Option<VersionObject> {
+        self.crates
+            .iter()
+            .find(|c| c.name == name && &c.version == version)
+            .cloned()
+            .map(VersionObject)
+    }
+}
+
+async fn load(ctx: &Context<'_>, name: String, version: Version) -> Result<Option<VersionObject>> {
+    let loader = ctx.data_unchecked::<DataLoader<CrateLoader>>();
+
+    Ok(loader
+        .load_one((name, version))
+        .await
+        .map_err(Error::new)?
+        .map(VersionObject))
+}
+
+/// Metadata of the crate, looked up when it was resolved without it.
+async fn metadata(ctx: &Context<'_>, c: &Crate) -> Result<CrateMetadata> {
+    if c.metadata.checksum.is_some() {
+        return Ok(c.metadata.clone());
+    }
+
+    let loader = ctx.data_unchecked::<DataLoader<MetadataLoader>>();
+
+    Ok(loader
+        .load_one((c.name.to_owned(), c.version.to_owned()))
+        .await
+        .map_err(Error::new)?
+        .unwrap_or_default())
+}
+
+async fn graph(
+    ctx: &Context<'_>,
+    name: String,
+    version: Version,
+    depth: Option<usize>,
+    exclude: Option<Vec<String>>,
+    prune: Option<Vec<String>>,
+) -> Result<GraphObject> {
+    let pools = ctx.data_unchecked::<Arc<Pools>>();
+
+    let crates = pools
+        .data()
+        .with_rules(Rules::new(
+            exclude.unwrap_or_default(),
+            prune.unwrap_or_default(),
+        ))
+        .get_dependency_graph(name.to_owned(), version.to_owned(), depth)
+        .await
+        .map_err(Error::new)?;
+
+    Ok(GraphObject {
+        name,
+        version,
+        crates,
+    })
+}
+
+fn parse_version(version: &str) -> Result<Version> {
+    Version::parse(version).map_err(|e| Error::new(format!("version invalid: {:?}", e)))
+}
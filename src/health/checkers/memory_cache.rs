@@ -0,0 +1,55 @@
+use crate::cache::MemoryCache;
+use crate::health::checkers::{get_time, HealthCheckerAction};
+use crate::health::HealthCheck;
+use crate::health::HealthStatus;
+use std::collections::HashMap;
+
+pub(crate) struct MemoryCacheHealthChecker<'a> {
+    memory_cache: &'a MemoryCache,
+}
+
+impl<'a> MemoryCacheHealthChecker<'a> {
+    pub(crate) fn new(memory_cache: &'a MemoryCache) -> Self {
+        Self { memory_cache }
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> HealthCheckerAction for MemoryCacheHealthChecker<'a> {
+    async fn check(&self) -> HealthCheck {
+        let hits = self.memory_cache.hits();
+        let misses = self.memory_cache.misses();
+        let (entries, crates) = self.memory_cache.size();
+
+        let mut additional_keys = HashMap::new();
+        additional_keys.insert("hits".to_owned(), hits.to_string());
+        additional_keys.insert("misses".to_owned(), misses.to_string());
+        additional_keys.insert(
+            "evictions".to_owned(),
+            self.memory_cache.evictions().to_string(),
+        );
+        additional_keys.insert("entries".to_owned(), entries.to_string());
+        additional_keys.insert(
+            "capacity".to_owned(),
+            self.memory_cache.capacity().to_string(),
+        );
+
+        HealthCheck {
+            component_name: "cache:memory".to_owned(),
+            component_id: None,
+            component_type: Some("component".to_owned()),
+            observed_value: Some(crates.to_string()),
+            observed_unit: Some("crates".to_owned()),
+            status: Some(HealthStatus::Pass),
+            affected_endpoints: None,
+            time: get_time(),
+            output: if self.memory_cache.enabled() {
+                None
+            } else {
+                Some("disabled".to_owned())
+            },
+            links: None,
+            additional_keys: Some(additional_keys),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 mod internet_http_connectivity;
 mod internet_https_connectivity;
+mod memory_cache;
 mod mysql_connectivity;
 mod redis_connectivity;
+mod resolution_concurrency;
 mod uptime;
 
 use crate::health::HealthCheck;
@@ -10,8 +12,10 @@ use chrono::{SecondsFormat, Utc};
 
 pub(crate) use internet_http_connectivity::InternetHttpConnectivityHealthChecker;
 pub(crate) use internet_https_connectivity::InternetHttpsConnectivityHealthChecker;
+pub(crate) use memory_cache::MemoryCacheHealthChecker;
 pub(crate) use mysql_connectivity::MySqlConnectivityHealthChecker;
 pub(crate) use redis_connectivity::RedisConnectivityHealthChecker;
+pub(crate) use resolution_concurrency::ResolutionConcurrencyHealthChecker;
 pub(crate) use uptime::UptimeHealthChecker;
 
 #[async_trait::async_trait]
@@ -0,0 +1,50 @@
+use crate::concurrency::Limiter;
+use crate::health::checkers::{get_time, HealthCheckerAction};
+use crate::health::HealthCheck;
+use crate::health::HealthStatus;
+use std::collections::HashMap;
+
+pub(crate) struct ResolutionConcurrencyHealthChecker<'a> {
+    limiter: &'a Limiter,
+}
+
+impl<'a> ResolutionConcurrencyHealthChecker<'a> {
+    pub(crate) fn new(limiter: &'a Limiter) -> Self {
+        Self { limiter }
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> HealthCheckerAction for ResolutionConcurrencyHealthChecker<'a> {
+    async fn check(&self) -> HealthCheck {
+        let waiting = self.limiter.waiting();
+        let running = self.limiter.running();
+
+        let mut additional_keys = HashMap::new();
+        additional_keys.insert("running".to_owned(), running.to_string());
+        additional_keys.insert("global".to_owned(), self.limiter.global().to_string());
+        additional_keys.insert(
+            "resolution".to_owned(),
+            self.limiter.resolution().to_string(),
+        );
+
+        HealthCheck {
+            component_name: "resolution:concurrency".to_owned(),
+            component_id: None,
+            component_type: Some("system".to_owned()),
+            observed_value: Some(waiting.to_string()),
+            observed_unit: Some("tasks".to_owned()),
+            // a queue deeper than the global limit means requests wait longer than a task takes.
+            status: if waiting > self.limiter.global() {
+                Some(HealthStatus::Warn)
+            } else {
+                Some(HealthStatus::Pass)
+            },
+            affected_endpoints: None,
+            time: get_time(),
+            output: None,
+            links: None,
+            additional_keys: Some(additional_keys),
+        }
+    }
+}
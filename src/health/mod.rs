@@ -1,11 +1,15 @@
 mod checkers;
 mod models;
 
+use crate::cache::MemoryCache;
+use crate::concurrency::Limiter;
 use checkers::HealthCheckerAction;
 use checkers::InternetHttpConnectivityHealthChecker;
 use checkers::InternetHttpsConnectivityHealthChecker;
+use checkers::MemoryCacheHealthChecker;
 use checkers::MySqlConnectivityHealthChecker;
 use checkers::RedisConnectivityHealthChecker;
+use checkers::ResolutionConcurrencyHealthChecker;
 use checkers::UptimeHealthChecker;
 
 pub(crate) use models::{Health, HealthCheck, HealthStatus};
@@ -13,8 +17,10 @@ pub(crate) use models::{Health, HealthCheck, HealthStatus};
 pub(crate) struct HealthChecker<'a> {
     internet_http_connectivity: InternetHttpConnectivityHealthChecker<'a>,
     internet_https_connectivity: InternetHttpsConnectivityHealthChecker<'a>,
+    memory_cache: MemoryCacheHealthChecker<'a>,
     mysql_connectivity: MySqlConnectivityHealthChecker<'a>,
     redis_connectivity: RedisConnectivityHealthChecker<'a>,
+    resolution_concurrency: ResolutionConcurrencyHealthChecker<'a>,
     uptime: UptimeHealthChecker,
 }
 
@@ -23,6 +29,8 @@ impl<'a> HealthChecker<'a> {
         database_pool: &'a sqlx::MySqlPool,
         http_client_pool: &'a reqwest::Client,
         redis_pool: &'a redis::aio::MultiplexedConnection,
+        limiter: &'a Limiter,
+        memory_cache: &'a MemoryCache,
     ) -> Self {
         Self {
             internet_http_connectivity: InternetHttpConnectivityHealthChecker::new(
@@ -31,8 +39,10 @@ impl<'a> HealthChecker<'a> {
             internet_https_connectivity: InternetHttpsConnectivityHealthChecker::new(
                 http_client_pool,
             ),
+            memory_cache: MemoryCacheHealthChecker::new(memory_cache),
             mysql_connectivity: MySqlConnectivityHealthChecker::new(database_pool),
             redis_connectivity: RedisConnectivityHealthChecker::new(redis_pool),
+            resolution_concurrency: ResolutionConcurrencyHealthChecker::new(limiter),
             uptime: UptimeHealthChecker::new(),
         }
     }
@@ -41,8 +51,10 @@ impl<'a> HealthChecker<'a> {
         let health_checks = futures::future::join_all(vec![
             self.internet_http_connectivity.check(),
             self.internet_https_connectivity.check(),
+            self.memory_cache.check(),
             self.mysql_connectivity.check(),
             self.redis_connectivity.check(),
+            self.resolution_concurrency.check(),
             self.uptime.check(),
         ])
         .await;
@@ -0,0 +1,254 @@
+use crate::cache::{Cache, CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
+use crate::data::{Data, ResolutionEvent};
+use crate::domain::{Job, JobProgress, JobStatus};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::StreamExt;
+use semver::Version;
+use std::time::Duration;
+
+/// How long a queued or running job may go without a heartbeat before it is taken to have died
+/// with the process running it.
+const LEASE_SECONDS: i64 = 60;
+
+/// How often a running job heartbeats, well within its lease.
+const HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// What a running job reacts to.
+enum Signal {
+    Event(ResolutionEvent),
+    Heartbeat,
+    Resolved,
+}
+
+/// Resolves dependency graphs in the background, keeping their state in the cache so any
+/// process can report on them. A job is run by the process it was submitted to, which renews its
+/// lease by saving it at least every `HEARTBEAT`.
+pub(crate) struct Jobs<'a> {
+    database_pool: &'a sqlx::MySqlPool,
+    http_client_pool: &'a reqwest::Client,
+    redis_pool: &'a redis::aio::MultiplexedConnection,
+    limiter: &'a Limiter,
+    memory_cache: &'a MemoryCache,
+    cache_config: &'a CacheConfig,
+    cache: Cache<'a>,
+}
+
+impl<'a> Jobs<'a> {
+    pub(crate) fn new(
+        database_pool: &'a sqlx::MySqlPool,
+        http_client_pool: &'a reqwest::Client,
+        redis_pool: &'a redis::aio::MultiplexedConnection,
+        limiter: &'a Limiter,
+        memory_cache: &'a MemoryCache,
+        cache_config: &'a CacheConfig,
+    ) -> Self {
+        Self {
+            database_pool,
+            http_client_pool,
+            redis_pool,
+            limiter,
+            memory_cache,
+            cache_config,
+            cache: Cache::new(redis_pool, memory_cache, cache_config),
+        }
+    }
+
+    /// Records a queued job, to be run by `execute`.
+    pub(crate) async fn submit(
+        &self,
+        name: String,
+        version: Version,
+        depth: Option<usize>,
+    ) -> Result<Job, String> {
+        let now = Self::now();
+
+        let job = Job {
+            id: self.cache.next_job_id().await?,
+            name,
+            version,
+            depth,
+            status: JobStatus::Queued,
+            progress: JobProgress::default(),
+            result: None,
+            error: None,
+            created_at: now.to_owned(),
+            updated_at: now,
+        };
+
+        self.cache.save_job(&job).await?;
+
+        Ok(job)
+    }
+
+    /// Gets the job, failing it if its lease has expired.
+    pub(crate) async fn get(&self, id: &str) -> Result<Option<Job>, String> {
+        let mut job = match self.cache.get_job(id).await? {
+            Some(job) => job,
+            None => return Ok(None),
+        };
+
+        if Self::abandoned(&job, Utc::now()) {
+            job.status = JobStatus::Failed;
+            job.error = Some(format!(
+                "job abandoned: no heartbeat since {}",
+                job.updated_at
+            ));
+            self.save(&mut job).await?;
+        }
+
+        Ok(Some(job))
+    }
+
+    /// Resolves the job, saving its progress after every layer and every heartbeat, and its
+    /// outcome at the end.
+    pub(crate) async fn execute(&self, mut job: Job) -> Result<(), String> {
+        let fn_name = "execute";
+
+        job.status = JobStatus::Running;
+        self.save(&mut job).await?;
+
+        let (sender, mut receiver) = futures::channel::mpsc::unbounded();
+
+        let data = Data::new(
+            self.database_pool,
+            self.http_client_pool,
+            self.redis_pool,
+            self.limiter,
+            self.memory_cache,
+            self.cache_config,
+        )
+        .with_events(sender)
+        .without_complete_event();
+
+        let name = job.name.to_owned();
+        let version = job.version.to_owned();
+        let depth = job.depth;
+
+        // dropping `data` once resolved closes the event stream.
+        let resolution = async move { data.get_dependency_graph(name, version, depth).await };
+
+        let events =
+            receiver
+                .map(Signal::Event)
+                .chain(futures::stream::once(futures::future::ready(
+                    Signal::Resolved,
+                )));
+        let heartbeats = futures::stream::unfold(
+            actix_web::rt::time::interval(HEARTBEAT),
+            |mut interval| async move {
+                interval.tick().await;
+                Some((Signal::Heartbeat, interval))
+            },
+        );
+        let signals = futures::stream::select(events, heartbeats);
+        futures::pin_mut!(signals);
+
+        let progress = async {
+            while let Some(signal) = signals.next().await {
+                match signal {
+                    Signal::Event(ResolutionEvent::Layer { depth, .. }) => {
+                        job.progress.depth = depth;
+                    }
+                    Signal::Event(ResolutionEvent::Node(_, _)) => {
+                        job.progress.crates += 1;
+                        continue;
+                    }
+                    Signal::Event(_) => continue,
+                    Signal::Heartbeat => {}
+                    Signal::Resolved => break,
+                }
+
+                if let Err(e) = self.save(&mut job).await {
+                    log::warn!(
+                        "{}: progress not saved: id={:?} error={}",
+                        fn_name,
+                        job.id,
+                        e
+                    );
+                }
+            }
+        };
+
+        let (result, _) = futures::future::join(resolution, progress).await;
+
+        match result {
+            Ok(crates) => {
+                job.status = JobStatus::Completed;
+                job.result = Some(crates);
+            }
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+
+        self.save(&mut job).await
+    }
+
+    async fn save(&self, job: &mut Job) -> Result<(), String> {
+        job.updated_at = Self::now();
+        self.cache.save_job(job).await
+    }
+
+    /// Whether the job is unfinished but has not been saved within its lease.
+    fn abandoned(job: &Job, now: DateTime<Utc>) -> bool {
+        if !matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+            return false;
+        }
+
+        match DateTime::parse_from_rfc3339(&job.updated_at) {
+            Ok(updated_at) => now.signed_duration_since(updated_at).num_seconds() > LEASE_SECONDS,
+            Err(_) => true,
+        }
+    }
+
+    fn now() -> String {
+        Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(status: JobStatus, updated_at: &str) -> Job {
+        Job {
+            id: "1".to_owned(),
+            name: "a".to_owned(),
+            version: Version::new(1, 0, 0),
+            depth: None,
+            status,
+            progress: JobProgress::default(),
+            result: None,
+            error: None,
+            created_at: "2020-10-01T00:00:00Z".to_owned(),
+            updated_at: updated_at.to_owned(),
+        }
+    }
+
+    #[test]
+    fn abandoned_after_lease() {
+        let now = DateTime::parse_from_rfc3339("2020-10-01T00:01:01Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(Jobs::abandoned(
+            &job(JobStatus::Running, "2020-10-01T00:00:00Z"),
+            now
+        ));
+        assert!(Jobs::abandoned(
+            &job(JobStatus::Queued, "2020-10-01T00:00:00Z"),
+            now
+        ));
+        assert!(!Jobs::abandoned(
+            &job(JobStatus::Running, "2020-10-01T00:00:30Z"),
+            now
+        ));
+        assert!(!Jobs::abandoned(
+            &job(JobStatus::Completed, "2020-10-01T00:00:00Z"),
+            now
+        ));
+        assert!(Jobs::abandoned(&job(JobStatus::Running, "invalid"), now));
+    }
+}
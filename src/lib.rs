@@ -1,17 +1,27 @@
 mod api;
 mod cache;
+mod concurrency;
 mod config;
 mod data;
 mod domain;
 mod factory;
+mod formats;
+mod graph;
+mod graphql;
 mod health;
+mod jobs;
 mod persistence;
 mod routes;
+#[cfg(test)]
+mod test_support;
 
+use crate::cache::{CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
 use crate::factory::database_pool;
 use crate::factory::http_client_pool;
 use crate::factory::redis_pool;
-use actix_web::{App, HttpServer};
+use crate::routes::AdminConfig;
+use actix_web::{web, App, HttpServer};
 
 pub use config::Config;
 
@@ -22,11 +32,57 @@ pub async fn run(config: &Config) -> Result<(), String> {
     let http_client_pool = http_client_pool::new()?;
     let redis_pool = redis_pool::new(&config.redis_url).await?;
 
+    // shared by every worker, so the global limit spans all requests.
+    let limiter = web::Data::new(Limiter::new(
+        config.resolution_concurrency,
+        config.global_concurrency,
+    ));
+
+    let cache_config = web::Data::new(CacheConfig {
+        dependencies_enabled: config.dependencies_enabled,
+        dependencies_ttl: config.dependencies_ttl,
+        nodes_ttl: config.nodes_ttl,
+        jobs_ttl: config.jobs_ttl,
+    });
+
+    // shared by every worker, so a graph resolved by one is served from memory by all.
+    let memory_cache = web::Data::new(MemoryCache::new(if config.memory_enabled {
+        config.memory_capacity
+    } else {
+        0
+    }));
+
+    // entries invalidated by any instance are dropped from the memory of this one.
+    if memory_cache.enabled() {
+        actix_web::rt::spawn(cache::subscribe(
+            config.redis_url.to_owned(),
+            memory_cache.clone().into_inner(),
+        ));
+    }
+
+    let admin_config = web::Data::new(AdminConfig {
+        token: config.admin_token.clone(),
+    });
+
+    let schema = graphql::schema(
+        database_pool.clone(),
+        http_client_pool.clone(),
+        redis_pool.clone(),
+        limiter.clone().into_inner(),
+        memory_cache.clone().into_inner(),
+        cache_config.clone().into_inner(),
+    );
+
     HttpServer::new(move || {
         App::new()
             .data(database_pool.clone())
             .data(http_client_pool.clone())
             .data(redis_pool.clone())
+            .app_data(limiter.clone())
+            .app_data(memory_cache.clone())
+            .app_data(cache_config.clone())
+            .app_data(admin_config.clone())
+            .data(schema.clone())
             .configure(routes::configure)
     })
     .bind(&config.server_address)
@@ -1,5 +1,6 @@
 use rust_kata_001::Config;
 use std::env;
+use std::str::FromStr;
 
 #[actix_web::main]
 async fn main() -> Result<(), String> {
@@ -11,13 +12,49 @@ async fn main() -> Result<(), String> {
     let server_address_host = resolve_parameter("SERVER_ADDRESS_HOST")?;
     let server_address_port = resolve_parameter("SERVER_ADDRESS_PORT")?;
 
-    let config = Config::new(
+    let mut config = Config::new(
         &mysql_url,
         &redis_url,
         &server_address_host,
         &server_address_port,
     );
 
+    if let Some(resolution_concurrency) = resolve_optional_parameter("RESOLUTION_CONCURRENCY")? {
+        config = config.with_resolution_concurrency(resolution_concurrency);
+    }
+
+    if let Some(global_concurrency) = resolve_optional_parameter("GLOBAL_CONCURRENCY")? {
+        config = config.with_global_concurrency(global_concurrency);
+    }
+
+    if let Some(dependencies_enabled) = resolve_optional_parameter("CACHE_DEPENDENCIES_ENABLED")? {
+        config = config.with_dependencies_enabled(dependencies_enabled);
+    }
+
+    if let Some(dependencies_ttl) = resolve_optional_parameter("CACHE_DEPENDENCIES_TTL")? {
+        config = config.with_dependencies_ttl(dependencies_ttl);
+    }
+
+    if let Some(nodes_ttl) = resolve_optional_parameter("CACHE_NODES_TTL")? {
+        config = config.with_nodes_ttl(nodes_ttl);
+    }
+
+    if let Some(jobs_ttl) = resolve_optional_parameter("CACHE_JOBS_TTL")? {
+        config = config.with_jobs_ttl(jobs_ttl);
+    }
+
+    if let Some(memory_enabled) = resolve_optional_parameter("CACHE_MEMORY_ENABLED")? {
+        config = config.with_memory_enabled(memory_enabled);
+    }
+
+    if let Some(memory_capacity) = resolve_optional_parameter("CACHE_MEMORY_CAPACITY")? {
+        config = config.with_memory_capacity(memory_capacity);
+    }
+
+    if let Some(admin_token) = resolve_optional_parameter::<String>("ADMIN_TOKEN")? {
+        config = config.with_admin_token(&admin_token);
+    }
+
     rust_kata_001::run(&config).await
 }
 
@@ -29,3 +66,23 @@ fn resolve_parameter(key: &str) -> Result<String, String> {
         format!("{}: key={:?} error={:?}", fn_name, key, error)
     })
 }
+
+fn resolve_optional_parameter<T>(key: &str) -> Result<Option<T>, String>
+where
+    T: FromStr,
+    T::Err: std::fmt::Debug,
+{
+    let fn_name = "resolve_optional_parameter";
+
+    match env::var(key) {
+        Ok(value) => value.parse().map(Some).map_err(|error| {
+            log::error!("{}: key={:?} error={:?}", fn_name, key, error);
+            format!("{}: key={:?} error={:?}", fn_name, key, error)
+        }),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(error) => {
+            log::error!("{}: key={:?} error={:?}", fn_name, key, error);
+            Err(format!("{}: key={:?} error={:?}", fn_name, key, error))
+        }
+    }
+}
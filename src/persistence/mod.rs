@@ -1,6 +1,6 @@
 mod relational_database;
 
-use crate::domain::{Crate, CrateDependency};
+use crate::domain::{Crate, CrateDependency, CrateMetadata, DependencyKind};
 use crate::persistence::relational_database::{CrateDataDto, RelationalDatabase};
 use semver::Version;
 use sqlx::MySqlPool;
@@ -42,6 +42,23 @@ impl<'a> Persistence<'a> {
         self.relational_database.save_one(c).await
     }
 
+    /// Saves a crate, replacing the dependencies of the one already saved.
+    pub(crate) async fn replace_one(&self, c: &Crate) -> Result<(), String> {
+        self.relational_database.replace_one(c).await
+    }
+
+    /// Records the metadata of a crate already saved.
+    pub(crate) async fn save_metadata(
+        &self,
+        name: &str,
+        version: &Version,
+        metadata: &CrateMetadata,
+    ) -> Result<(), String> {
+        self.relational_database
+            .save_metadata(name, version, metadata)
+            .await
+    }
+
     fn transform_to_domain(dtos: &[CrateDataDto]) -> Vec<Crate> {
         let fn_name = "transform_to_domain";
 
@@ -80,6 +97,13 @@ impl<'a> Persistence<'a> {
                 name: name.to_string(),
                 version: Version::parse(version).unwrap(),
                 dependency: Vec::new(),
+                metadata: group
+                    .first()
+                    .map(|&g| CrateMetadata {
+                        license: g.license.to_owned(),
+                        checksum: g.checksum.to_owned(),
+                    })
+                    .unwrap_or_default(),
             };
 
             for item in group {
@@ -88,6 +112,13 @@ impl<'a> Persistence<'a> {
                         web_dto.dependency.push(CrateDependency {
                             name: name.to_owned(),
                             version: Version::parse(version).unwrap(),
+                            req: item.dependency_req.to_owned(),
+                            kind: item
+                                .dependency_kind
+                                .as_deref()
+                                .and_then(DependencyKind::parse)
+                                .unwrap_or(DependencyKind::Normal),
+                            optional: item.dependency_optional.unwrap_or_default(),
                         });
                     }
                 }
@@ -111,29 +142,49 @@ mod tests {
                 name: "name 1".to_owned(),
                 version: "1.0.0".to_owned(),
                 dependencies: 2,
+                license: Some("MIT".to_owned()),
+                checksum: None,
                 dependency_name: Some("sub name 1".to_owned()),
                 dependency_version: Some("0.0.1".to_owned()),
+                dependency_kind: Some("normal".to_owned()),
+                dependency_optional: Some(false),
+                dependency_req: Some("^0.0.1".to_owned()),
             },
             CrateDataDto {
                 name: "name 1".to_owned(),
                 version: "1.0.0".to_owned(),
                 dependencies: 2,
+                license: Some("MIT".to_owned()),
+                checksum: None,
                 dependency_name: Some("sub name 2".to_owned()),
                 dependency_version: Some("0.0.2".to_owned()),
+                dependency_kind: Some("build".to_owned()),
+                dependency_optional: Some(true),
+                dependency_req: None,
             },
             CrateDataDto {
                 name: "name 2".to_owned(),
                 version: "2.0.0".to_owned(),
                 dependencies: 1,
+                license: None,
+                checksum: None,
                 dependency_name: Some("sub name 1".to_owned()),
                 dependency_version: Some("0.0.1".to_owned()),
+                dependency_kind: Some("normal".to_owned()),
+                dependency_optional: Some(false),
+                dependency_req: Some("^0.0.1".to_owned()),
             },
             CrateDataDto {
                 name: "name 3".to_owned(),
                 version: "3.0.0".to_owned(),
                 dependencies: 3,
+                license: None,
+                checksum: None,
                 dependency_name: Some("sub name 1".to_owned()),
                 dependency_version: Some("0.0.1".to_owned()),
+                dependency_kind: Some("normal".to_owned()),
+                dependency_optional: Some(false),
+                dependency_req: Some("^0.0.1".to_owned()),
             },
         ];
 
@@ -145,12 +196,22 @@ mod tests {
                     CrateDependency {
                         name: "sub name 1".to_owned(),
                         version: Version::parse("0.0.1").unwrap(),
+                        req: Some("^0.0.1".to_owned()),
+                        kind: DependencyKind::Normal,
+                        optional: false,
                     },
                     CrateDependency {
                         name: "sub name 2".to_owned(),
                         version: Version::parse("0.0.2").unwrap(),
+                        req: None,
+                        kind: DependencyKind::Build,
+                        optional: true,
                     },
                 ],
+                metadata: CrateMetadata {
+                    license: Some("MIT".to_owned()),
+                    checksum: None,
+                },
             },
             Crate {
                 name: "name 2".to_owned(),
@@ -158,7 +219,11 @@ mod tests {
                 dependency: vec![CrateDependency {
                     name: "sub name 1".to_owned(),
                     version: Version::parse("0.0.1").unwrap(),
+                    req: Some("^0.0.1".to_owned()),
+                    kind: DependencyKind::Normal,
+                    optional: false,
                 }],
+                metadata: CrateMetadata::default(),
             },
         ];
 
@@ -1,4 +1,4 @@
-use crate::domain::Crate;
+use crate::domain::{Crate, CrateMetadata};
 use semver::Version;
 use sqlx::{MySqlPool, Row};
 
@@ -6,8 +6,13 @@ pub(crate) struct CrateDataDto {
     pub(crate) name: String,
     pub(crate) version: String,
     pub(crate) dependencies: i32,
+    pub(crate) license: Option<String>,
+    pub(crate) checksum: Option<String>,
     pub(crate) dependency_name: Option<String>,
     pub(crate) dependency_version: Option<String>,
+    pub(crate) dependency_kind: Option<String>,
+    pub(crate) dependency_optional: Option<bool>,
+    pub(crate) dependency_req: Option<String>,
 }
 
 pub(crate) struct RelationalDatabase<'a> {
@@ -25,11 +30,12 @@ impl<'a> RelationalDatabase<'a> {
     ) -> Result<Vec<CrateDataDto>, String> {
         let fn_name = "get_many";
 
-        let mut sql = "SELECT c.name, c.version, c.dependencies, cd.name, cd.version
+        let mut sql =
+            "SELECT c.name, c.version, c.dependencies, cd.name, cd.version, cd.kind, cd.optional, c.license, c.checksum, cd.req
 FROM crate c
          LEFT JOIN crate_dependency cd on c.id = cd.crate_id
 WHERE (c.name = ? AND c.version = ?)"
-            .to_string();
+                .to_string();
 
         for _ in 1..name_version.len() {
             sql += "
@@ -54,8 +60,13 @@ WHERE (c.name = ? AND c.version = ?)"
                 name: record.get(0),
                 version: record.get(1),
                 dependencies: record.get(2),
+                license: record.get(7),
+                checksum: record.get(8),
                 dependency_name: record.get(3),
                 dependency_version: record.get(4),
+                dependency_kind: record.get(5),
+                dependency_optional: record.get(6),
+                dependency_req: record.get(9),
             });
         }
 
@@ -68,12 +79,14 @@ WHERE (c.name = ? AND c.version = ?)"
         log::info!("{}: crate={:?}", fn_name, c);
 
         sqlx::query(
-            "INSERT INTO crate (name, version, dependencies) VALUE (?, ?, ?)
+            "INSERT INTO crate (name, version, dependencies, license, checksum) VALUE (?, ?, ?, ?, ?)
 ON DUPLICATE KEY UPDATE id=LAST_INSERT_ID(id)",
         )
         .bind(&c.name)
         .bind(c.version.to_string())
         .bind(c.dependency.len() as i32)
+        .bind(&c.metadata.license)
+        .bind(&c.metadata.checksum)
         .execute(self.pool)
         .await
         .map_err(|e| {
@@ -100,12 +113,15 @@ WHERE name = ?
 
         for d in &c.dependency {
             sqlx::query(
-                "INSERT INTO crate_dependency (crate_id, name, version) VALUE (?, ?, ?)
+                "INSERT INTO crate_dependency (crate_id, name, version, req, kind, optional) VALUE (?, ?, ?, ?, ?, ?)
 ON DUPLICATE KEY UPDATE id=LAST_INSERT_ID(id)",
             )
             .bind(id)
             .bind(&d.name)
             .bind(d.version.to_string())
+            .bind(&d.req)
+            .bind(d.kind.as_str())
+            .bind(d.optional)
             .execute(self.pool)
             .await
             .map_err(|e| {
@@ -116,6 +132,106 @@ ON DUPLICATE KEY UPDATE id=LAST_INSERT_ID(id)",
 
         Ok(())
     }
+
+    /// Saves a crate, replacing the dependencies of the one already saved. Metadata already
+    /// saved is kept when the crate comes without it.
+    pub(crate) async fn replace_one(&self, c: &Crate) -> Result<(), String> {
+        let fn_name = "replace_one";
+
+        log::info!("{}: crate={:?}", fn_name, c);
+
+        let map_err = |e| {
+            log::error!("{}: error {:?}", fn_name, e);
+            format!("{}: error {:?}", fn_name, e)
+        };
+
+        // readers checking `dependencies` against the rows see either version, never a mix.
+        let mut transaction = self.pool.begin().await.map_err(map_err)?;
+
+        sqlx::query(
+            "INSERT INTO crate (name, version, dependencies, license, checksum) VALUE (?, ?, ?, ?, ?)
+ON DUPLICATE KEY UPDATE id=LAST_INSERT_ID(id),
+                        dependencies=VALUES(dependencies),
+                        license=COALESCE(VALUES(license), license),
+                        checksum=COALESCE(VALUES(checksum), checksum)",
+        )
+        .bind(&c.name)
+        .bind(c.version.to_string())
+        .bind(c.dependency.len() as i32)
+        .bind(&c.metadata.license)
+        .bind(&c.metadata.checksum)
+        .execute(&mut transaction)
+        .await
+        .map_err(map_err)?;
+
+        let row = sqlx::query(
+            "SELECT c.id
+FROM crate c
+WHERE name = ?
+  AND version = ?",
+        )
+        .bind(&c.name)
+        .bind(c.version.to_string())
+        .fetch_one(&mut transaction)
+        .await
+        .map_err(map_err)?;
+
+        let id: i32 = row.get(0);
+
+        sqlx::query("DELETE FROM crate_dependency WHERE crate_id = ?")
+            .bind(id)
+            .execute(&mut transaction)
+            .await
+            .map_err(map_err)?;
+
+        for d in &c.dependency {
+            sqlx::query(
+                "INSERT INTO crate_dependency (crate_id, name, version, req, kind, optional) VALUE (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(&d.name)
+            .bind(d.version.to_string())
+            .bind(&d.req)
+            .bind(d.kind.as_str())
+            .bind(d.optional)
+            .execute(&mut transaction)
+            .await
+            .map_err(map_err)?;
+        }
+
+        transaction.commit().await.map_err(map_err)?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn save_metadata(
+        &self,
+        name: &str,
+        version: &Version,
+        metadata: &CrateMetadata,
+    ) -> Result<(), String> {
+        let fn_name = "save_metadata";
+
+        sqlx::query(
+            "UPDATE crate
+SET license  = ?,
+    checksum = ?
+WHERE name = ?
+  AND version = ?",
+        )
+        .bind(&metadata.license)
+        .bind(&metadata.checksum)
+        .bind(name)
+        .bind(version.to_string())
+        .execute(self.pool)
+        .await
+        .map_err(|e| {
+            log::error!("{}: error {:?}", fn_name, e);
+            format!("{}: error {:?}", fn_name, e)
+        })?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,9 @@
+mod models;
+mod routes;
+
+use actix_web::web::ServiceConfig;
+use routes::{purge, refresh};
+
+pub(crate) fn configure(service_config: &mut ServiceConfig) {
+    service_config.service(purge).service(refresh);
+}
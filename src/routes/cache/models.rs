@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub(crate) struct PurgeQueryParams {
+    pub(crate) name: Option<String>,
+    pub(crate) version: Option<String>,
+    pub(crate) pattern: Option<String>,
+    pub(crate) confirm: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RefreshQueryParams {
+    pub(crate) name: Option<String>,
+    pub(crate) version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct PurgeWebDto {
+    pub(crate) deleted: usize,
+}
@@ -0,0 +1,140 @@
+use crate::cache::{escape_pattern, Cache, CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
+use crate::data::Data;
+use crate::routes::cache::models;
+use crate::routes::{
+    authorize, bad_request, internal_server_error, required, required_version, AdminConfig,
+};
+use actix_web::{delete, post, web, HttpRequest, HttpResponse, Responder};
+use semver::Version;
+use sqlx::mysql;
+
+enum Target {
+    /// Graphs holding crate+version.
+    Crate(String, Version),
+    /// Graphs rooted at or holding a crate whose `{name}:{version}` matches.
+    Pattern(String),
+}
+
+#[delete("/dependencies")]
+pub(crate) async fn purge(
+    request: HttpRequest,
+    admin_config: web::Data<AdminConfig>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::PurgeQueryParams>,
+) -> impl Responder {
+    // request
+    if let Err(response) = authorize(&request, &admin_config) {
+        return response;
+    }
+
+    let target = match &query_parameters.pattern {
+        Some(_) if query_parameters.name.is_some() || query_parameters.version.is_some() => {
+            return bad_request("pattern invalid with name or version".to_owned())
+        }
+        Some(pattern) if matches_everything(pattern) && query_parameters.confirm != Some(true) => {
+            return bad_request("pattern matching every crate requires confirm=true".to_owned())
+        }
+        Some(pattern) => Target::Pattern(pattern.to_owned()),
+        None => {
+            let name = match required("name", &query_parameters.name) {
+                Ok(name) => name,
+                Err(response) => return response,
+            };
+
+            match &query_parameters.version {
+                Some(_) => match required_version("version", &query_parameters.version) {
+                    Ok(version) => Target::Crate(name, version),
+                    Err(response) => return response,
+                },
+                None => Target::Pattern(format!("{}:*", escape_pattern(&name))),
+            }
+        }
+    };
+
+    // data
+    let cache = Cache::new(
+        redis_pool.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    );
+
+    let result = match &target {
+        Target::Crate(name, version) => cache.invalidate(name, version).await,
+        Target::Pattern(pattern) => cache.purge(pattern).await,
+    };
+
+    // response
+    match result {
+        Ok(deleted) => HttpResponse::Ok().json(models::PurgeWebDto { deleted }),
+        Err(e) => internal_server_error(e),
+    }
+}
+
+#[post("/dependencies/refresh")]
+pub(crate) async fn refresh(
+    request: HttpRequest,
+    admin_config: web::Data<AdminConfig>,
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::RefreshQueryParams>,
+) -> impl Responder {
+    // request
+    if let Err(response) = authorize(&request, &admin_config) {
+        return response;
+    }
+
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &query_parameters.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    // data
+    let result = Data::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    )
+    .refresh_crate(&name, &version)
+    .await;
+
+    // response
+    match result {
+        Ok(deleted) => HttpResponse::Ok().json(models::PurgeWebDto { deleted }),
+        Err(e) => internal_server_error(e),
+    }
+}
+
+/// Whether the glob style pattern matches every `{name}:{version}`.
+fn matches_everything(pattern: &str) -> bool {
+    pattern.contains('*') && pattern.chars().all(|c| c == '*' || c == '?' || c == ':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_everything_only_for_wildcards() {
+        assert!(matches_everything("*"));
+        assert!(matches_everything("*:*"));
+        assert!(matches_everything("**"));
+        assert!(!matches_everything("tokio:*"));
+        assert!(!matches_everything("*:0.2.*"));
+        assert!(!matches_everything("?"));
+    }
+}
@@ -1,9 +1,17 @@
-use crate::routes::dependency::routes::list;
+use crate::routes::dependency::routes::{batch, diff, duplicates, events, list, order, stats};
 use actix_web::web::ServiceConfig;
 
 pub(crate) mod models;
+pub(crate) mod negotiation;
 pub(crate) mod routes;
 
 pub(crate) fn configure(service_config: &mut ServiceConfig) {
-    service_config.service(list);
+    service_config
+        .service(list)
+        .service(duplicates)
+        .service(batch)
+        .service(diff)
+        .service(events)
+        .service(stats)
+        .service(order);
 }
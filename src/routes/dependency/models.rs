@@ -1,36 +1,305 @@
+use crate::data::Source;
 use crate::domain::Crate;
+use crate::graph;
+use crate::graph::{
+    Degree, Duplicate, EdgeChange, GraphDiff, NodeAnnotation, NodeChange, Rules, Stats,
+};
+use crate::routes::dependency::routes::{DIFF_FORMATS, FORMATS, INCLUDE};
+use crate::routes::openapi::enumeration;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "Error")]
 pub(crate) struct ErrorWebDto {
+    /// HTTP status code.
     pub(crate) status_code: i32,
     pub(crate) error_message: String,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Crate")]
 pub(crate) struct CrateWebDto {
     name: String,
     version: String,
+    /// Minimum distance from the root.
+    depth: usize,
+    /// Number of incoming edges, left out when streamed as it needs the whole graph.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependents: Option<usize>,
+    /// Present when the dependencies were left unresolved by `prune`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pruned: bool,
     dependency: Vec<CrateDependencyWebDto>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "CrateDependency")]
 pub(crate) struct CrateDependencyWebDto {
     name: String,
+    /// Version the requirement was resolved to.
+    version: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Duplicate")]
+pub(crate) struct DuplicateWebDto {
+    name: String,
+    versions: Vec<DuplicateVersionWebDto>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "DuplicateVersion")]
+pub(crate) struct DuplicateVersionWebDto {
     version: String,
+    parents: Vec<CrateDependencyWebDto>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Order")]
+pub(crate) struct OrderWebDto {
+    /// Build order, leaves first, every level depending on the previous levels only.
+    levels: Vec<Vec<CrateDependencyWebDto>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Diff")]
+pub(crate) struct DiffWebDto {
+    name: String,
+    from: String,
+    to: String,
+    nodes: ChangesWebDto<NodeChangeWebDto>,
+    edges: ChangesWebDto<EdgeChangeWebDto>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Changes")]
+pub(crate) struct ChangesWebDto<T> {
+    added: Vec<T>,
+    removed: Vec<T>,
+    changed: Vec<T>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "NodeChange")]
+pub(crate) struct NodeChangeWebDto {
+    name: String,
+    from: Vec<String>,
+    to: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "EdgeChange")]
+pub(crate) struct EdgeChangeWebDto {
+    parent: String,
+    child: String,
+    from: Vec<String>,
+    to: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "CratesWithStats")]
+pub(crate) struct CratesWithStatsWebDto {
+    pub(crate) crates: Vec<CrateWebDto>,
+    pub(crate) stats: StatsWebDto,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Stats")]
+pub(crate) struct StatsWebDto {
+    nodes: usize,
+    edges: usize,
+    max_depth: usize,
+    mean_depth: f64,
+    widest_layer: LayerEventWebDto,
+    top_fan_in: Vec<DegreeWebDto>,
+    top_fan_out: Vec<DegreeWebDto>,
+    distinct_names: usize,
+    distinct_versions: usize,
+    sources: SourcesWebDto,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Degree")]
+pub(crate) struct DegreeWebDto {
+    name: String,
+    version: String,
+    count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Sources")]
+pub(crate) struct SourcesWebDto {
+    cache: SourceShareWebDto,
+    database: SourceShareWebDto,
+    upstream: SourceShareWebDto,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "SourceShare")]
+pub(crate) struct SourceShareWebDto {
+    crates: usize,
+    share: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+#[schemars(rename = "Layer")]
+pub(crate) struct LayerEventWebDto {
+    pub(crate) depth: usize,
+    pub(crate) crates: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct NodeEventWebDto {
+    name: String,
+    version: String,
+    source: String,
+    dependency: Vec<CrateDependencyWebDto>,
+}
+
+#[derive(Deserialize, JsonSchema)]
 pub(crate) struct ListQueryParams {
+    /// Name of the crate.
+    pub(crate) name: Option<String>,
+    /// Semantic version of the crate.
+    pub(crate) version: Option<String>,
+    /// Bounds the traversal, 1 returning the direct dependencies only.
+    pub(crate) depth: Option<usize>,
+    /// Comma separated additions to the JSON representation.
+    #[schemars(schema_with = "include_schema")]
+    pub(crate) include: Option<String>,
+    /// Leaves matching crates out of the graph. Comma separated crate name patterns, `*`
+    /// matching any run of characters and `?` any single character.
+    pub(crate) exclude: Option<String>,
+    /// Keeps matching crates as leaves. Comma separated crate name patterns, `*` matching any
+    /// run of characters and `?` any single character.
+    pub(crate) prune: Option<String>,
+    /// Representation of the graph, negotiated from the `Accept` header when absent.
+    #[schemars(schema_with = "format_schema")]
+    pub(crate) format: Option<String>,
+    /// Groups the versions of duplicate crates, with `format=dot`.
+    pub(crate) cluster: Option<bool>,
+    /// Shows the crates depending on the named crate, with `format=tree`.
+    pub(crate) invert: Option<String>,
+    /// Line prefix, with `format=tree`.
+    #[schemars(schema_with = "prefix_schema")]
+    pub(crate) prefix: Option<String>,
+    /// Draws the leaf dependencies of a crate as a single node, with `format=mermaid` or
+    /// `format=plantuml`.
+    pub(crate) collapse_leaves: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "BatchRequest")]
+pub(crate) struct BatchRequest {
+    pub(crate) roots: Vec<BatchRootRequest>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "BatchRoot")]
+pub(crate) struct BatchRootRequest {
     pub(crate) name: Option<String>,
     pub(crate) version: Option<String>,
 }
 
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "Batch")]
+pub(crate) struct BatchWebDto {
+    pub(crate) graphs: Vec<BatchGraphWebDto>,
+    /// The graphs merged, `depth` being the distance from the nearest root.
+    pub(crate) merged: Vec<CrateWebDto>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "BatchGraph")]
+pub(crate) struct BatchGraphWebDto {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) crates: Vec<CrateWebDto>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct DiffQueryParams {
+    /// Name of the crate.
+    pub(crate) name: Option<String>,
+    /// Semantic version compared from.
+    pub(crate) from: Option<String>,
+    /// Semantic version compared to.
+    pub(crate) to: Option<String>,
+    /// Bounds both graphs, 1 comparing the direct dependencies only.
+    pub(crate) depth: Option<usize>,
+    /// Representation of the changes.
+    #[schemars(schema_with = "diff_format_schema")]
+    pub(crate) format: Option<String>,
+}
+
+fn include_schema(_: &mut SchemaGenerator) -> Schema {
+    enumeration(INCLUDE)
+}
+
+fn format_schema(_: &mut SchemaGenerator) -> Schema {
+    enumeration(FORMATS)
+}
+
+fn prefix_schema(_: &mut SchemaGenerator) -> Schema {
+    enumeration(&["indent", "depth", "none"])
+}
+
+fn diff_format_schema(_: &mut SchemaGenerator) -> Schema {
+    enumeration(DIFF_FORMATS)
+}
+
 impl CrateWebDto {
-    pub(crate) fn transform(c: &Crate) -> Self {
+    /// Transforms the dependency graph of crate+version, annotating every crate.
+    pub(crate) fn annotated(name: &str, version: &Version, crates: &[Crate]) -> Vec<Self> {
+        Self::annotated_from(&[(name.to_owned(), version.to_owned())], crates)
+    }
+
+    /// Transforms the dependency graph of crate+version restricted by `rules`, annotating every
+    /// crate and marking the pruned ones.
+    pub(crate) fn annotated_with_rules(
+        name: &str,
+        version: &Version,
+        crates: &[Crate],
+        rules: &Rules,
+    ) -> Vec<Self> {
+        let mut results = Self::annotated(name, version, crates);
+
+        for c in &mut results {
+            c.pruned = rules.prunes(&c.name);
+        }
+
+        results
+    }
+
+    /// Transforms the dependency graph of many roots, annotating every crate.
+    pub(crate) fn annotated_from(roots: &[(String, Version)], crates: &[Crate]) -> Vec<Self> {
+        let annotations = graph::annotate_from(roots, crates);
+
+        crates
+            .iter()
+            .map(|c| {
+                let annotation = annotations
+                    .get(&(c.name.to_owned(), c.version.to_owned()))
+                    .cloned()
+                    .unwrap_or_default();
+
+                Self::transform(c, &annotation)
+            })
+            .collect()
+    }
+
+    /// Transforms a crate streamed at `depth`, before the rest of the graph is resolved.
+    pub(crate) fn streamed(c: &Crate, depth: usize, rules: &Rules) -> Self {
         Self {
             name: c.name.clone(),
             version: c.version.to_string(),
+            depth,
+            dependents: None,
+            pruned: rules.prunes(&c.name),
             dependency: c
                 .dependency
                 .iter()
@@ -41,13 +310,171 @@ impl CrateWebDto {
                 .collect(),
         }
     }
+
+    pub(crate) fn transform(c: &Crate, annotation: &NodeAnnotation) -> Self {
+        Self {
+            name: c.name.clone(),
+            version: c.version.to_string(),
+            depth: annotation.depth,
+            dependents: Some(annotation.dependents),
+            pruned: false,
+            dependency: c
+                .dependency
+                .iter()
+                .map(|d| CrateDependencyWebDto {
+                    name: d.name.clone(),
+                    version: d.version.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl NodeEventWebDto {
+    pub(crate) fn transform(c: &Crate, source: Source) -> Self {
+        Self {
+            name: c.name.clone(),
+            version: c.version.to_string(),
+            source: match source {
+                Source::Cache => "cache".to_owned(),
+                Source::Database => "database".to_owned(),
+                Source::Upstream => "upstream".to_owned(),
+            },
+            dependency: c
+                .dependency
+                .iter()
+                .map(|d| CrateDependencyWebDto {
+                    name: d.name.clone(),
+                    version: d.version.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl StatsWebDto {
+    pub(crate) fn transform(stats: &Stats, sources: &[Source]) -> Self {
+        let degree = |d: &Degree| DegreeWebDto {
+            name: d.name.clone(),
+            version: d.version.to_string(),
+            count: d.count,
+        };
+
+        let share = |source: Source| {
+            let crates = sources.iter().filter(|&&s| s == source).count();
+
+            SourceShareWebDto {
+                crates,
+                share: if sources.is_empty() {
+                    0.0
+                } else {
+                    crates as f64 / sources.len() as f64
+                },
+            }
+        };
+
+        Self {
+            nodes: stats.nodes,
+            edges: stats.edges,
+            max_depth: stats.max_depth,
+            mean_depth: stats.mean_depth,
+            widest_layer: LayerEventWebDto {
+                depth: stats.widest_layer.depth,
+                crates: stats.widest_layer.crates,
+            },
+            top_fan_in: stats.top_fan_in.iter().map(degree).collect(),
+            top_fan_out: stats.top_fan_out.iter().map(degree).collect(),
+            distinct_names: stats.distinct_names,
+            distinct_versions: stats.distinct_versions,
+            sources: SourcesWebDto {
+                cache: share(Source::Cache),
+                database: share(Source::Database),
+                upstream: share(Source::Upstream),
+            },
+        }
+    }
+}
+
+impl DuplicateWebDto {
+    pub(crate) fn transform(d: &Duplicate) -> Self {
+        Self {
+            name: d.name.clone(),
+            versions: d
+                .versions
+                .iter()
+                .map(|v| DuplicateVersionWebDto {
+                    version: v.version.to_string(),
+                    parents: v
+                        .parents
+                        .iter()
+                        .map(|(name, version)| CrateDependencyWebDto {
+                            name: name.clone(),
+                            version: version.to_string(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl OrderWebDto {
+    pub(crate) fn transform(levels: &[Vec<(String, Version)>]) -> Self {
+        Self {
+            levels: levels
+                .iter()
+                .map(|level| {
+                    level
+                        .iter()
+                        .map(|(name, version)| CrateDependencyWebDto {
+                            name: name.clone(),
+                            version: version.to_string(),
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DiffWebDto {
+    pub(crate) fn transform(name: &str, from: &Version, to: &Version, d: &GraphDiff) -> Self {
+        let node = |c: &NodeChange| NodeChangeWebDto {
+            name: c.name.clone(),
+            from: c.from.iter().map(|v| v.to_string()).collect(),
+            to: c.to.iter().map(|v| v.to_string()).collect(),
+        };
+
+        let edge = |c: &EdgeChange| EdgeChangeWebDto {
+            parent: c.parent.clone(),
+            child: c.child.clone(),
+            from: c.from.iter().map(|v| v.to_string()).collect(),
+            to: c.to.iter().map(|v| v.to_string()).collect(),
+        };
+
+        Self {
+            name: name.to_owned(),
+            from: from.to_string(),
+            to: to.to_string(),
+            nodes: ChangesWebDto {
+                added: d.added_nodes.iter().map(node).collect(),
+                removed: d.removed_nodes.iter().map(node).collect(),
+                changed: d.changed_nodes.iter().map(node).collect(),
+            },
+            edges: ChangesWebDto {
+                added: d.added_edges.iter().map(edge).collect(),
+                removed: d.removed_edges.iter().map(edge).collect(),
+                changed: d.changed_edges.iter().map(edge).collect(),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::CrateDependency;
-    use semver::Version;
+    use crate::domain::{CrateDependency, CrateMetadata, DependencyKind};
+    use serde_json::json;
 
     #[test]
     fn transform() {
@@ -58,17 +485,32 @@ mod tests {
                 CrateDependency {
                     name: "sub name 1".to_owned(),
                     version: Version::parse("0.0.1").unwrap(),
+                    req: None,
+                    kind: DependencyKind::Normal,
+                    optional: false,
                 },
                 CrateDependency {
                     name: "sub name 2".to_owned(),
                     version: Version::parse("0.0.2").unwrap(),
+                    req: None,
+                    kind: DependencyKind::Normal,
+                    optional: false,
                 },
             ],
+            metadata: CrateMetadata::default(),
+        };
+
+        let annotation = NodeAnnotation {
+            depth: 1,
+            dependents: 2,
         };
 
         let expected = CrateWebDto {
             name: "name".to_owned(),
             version: "1.0.0".to_owned(),
+            depth: 1,
+            dependents: Some(2),
+            pruned: false,
             dependency: vec![
                 CrateDependencyWebDto {
                     name: "sub name 1".to_owned(),
@@ -81,8 +523,27 @@ mod tests {
             ],
         };
 
-        let actual = CrateWebDto::transform(&input);
+        let actual = CrateWebDto::transform(&input, &annotation);
 
         assert_eq!(actual, expected)
     }
+
+    #[test]
+    fn streamed() {
+        let input = crate::test_support::node("name", "1.0.0", &[("sub name", "0.0.1")]);
+        let rules = Rules::new(Vec::new(), vec!["name".to_owned()]);
+
+        let actual = serde_json::to_value(CrateWebDto::streamed(&input, 3, &rules)).unwrap();
+
+        assert_eq!(
+            actual,
+            json!({
+                "name": "name",
+                "version": "1.0.0",
+                "depth": 3,
+                "pruned": true,
+                "dependency": [{ "name": "sub name", "version": "0.0.1" }]
+            })
+        );
+    }
 }
@@ -0,0 +1,90 @@
+/// Media types of the representations of `list`, with the `format` producing each. Wildcards
+/// such as `text/*` pick the first of their type.
+pub(crate) const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("application/json", "json"),
+    ("text/plain", "tree"),
+    ("text/vnd.graphviz", "dot"),
+    ("text/csv", "csv"),
+    ("text/spdx", "spdx-tag-value"),
+    ("application/vnd.cyclonedx+json", "cyclonedx"),
+    ("application/vnd.cyclonedx+xml", "cyclonedx-xml"),
+    ("application/spdx+json", "spdx"),
+    ("application/x-ndjson", "ndjson"),
+    ("image/svg+xml", "svg"),
+];
+
+/// Picks the format of the most preferred media type of an `Accept` header, JSON when the header
+/// is absent, `None` when none of the accepted types is supported.
+pub(crate) fn negotiate(accept: Option<&str>) -> Option<&'static str> {
+    let accept = match accept {
+        Some(accept) if !accept.trim().is_empty() => accept,
+        _ => return Some("json"),
+    };
+
+    let mut ranges = accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_type = parts.next()?.trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|parameter| {
+                    let (key, value) = split_once(parameter, '=')?;
+                    if key.trim().eq_ignore_ascii_case("q") {
+                        value.trim().parse::<f32>().ok()
+                    } else {
+                        None
+                    }
+                })
+                .next()
+                .unwrap_or(1.0);
+
+            Some((media_type, quality))
+        })
+        .filter(|(_, quality)| *quality > 0.0)
+        .collect::<Vec<_>>();
+
+    // stable, so equally preferred types keep the client's order.
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    ranges.iter().find_map(|(media_type, _)| {
+        if media_type == "*/*" {
+            return Some("json");
+        }
+
+        match media_type.strip_suffix("/*") {
+            Some(prefix) => MEDIA_TYPES
+                .iter()
+                .find(|(m, _)| split_once(m, '/').map(|(t, _)| t) == Some(prefix)),
+            None => MEDIA_TYPES.iter().find(|(m, _)| m == media_type),
+        }
+        .map(|(_, format)| *format)
+    })
+}
+
+// `str::split_once` is not stable in Rust 1.48.
+fn split_once(value: &str, delimiter: char) -> Option<(&str, &str)> {
+    let index = value.find(delimiter)?;
+    Some((&value[..index], &value[index + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_accept() {
+        assert_eq!(negotiate(None), Some("json"));
+        assert_eq!(negotiate(Some("*/*")), Some("json"));
+        assert_eq!(negotiate(Some("text/vnd.graphviz")), Some("dot"));
+        assert_eq!(
+            negotiate(Some("application/vnd.cyclonedx+json; version=1.4")),
+            Some("cyclonedx")
+        );
+        assert_eq!(
+            negotiate(Some("text/csv;q=0.5, application/x-ndjson")),
+            Some("ndjson")
+        );
+        assert_eq!(negotiate(Some("image/png, text/*;q=0.1")), Some("tree"));
+        assert_eq!(negotiate(Some("image/png, application/json;q=0")), None);
+    }
+}
@@ -1,43 +1,441 @@
-use crate::data::Data;
-use crate::routes::dependency::models;
-use actix_web::{get, web, HttpResponse, Responder};
+use crate::cache::{CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
+use crate::data::{Data, ResolutionEvent, Source};
+use crate::domain::Crate;
+use crate::formats;
+use crate::graph;
+use crate::graph::Rules;
+use crate::routes::dependency::{models, negotiation};
+use crate::routes::{
+    bad_request, internal_server_error, not_acceptable, required, required_version,
+    unprocessable_entity,
+};
+use actix_web::http::{header, HeaderValue};
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use chrono::{SecondsFormat, Utc};
+use futures::StreamExt;
 use semver::Version;
 use sqlx::mysql;
 
+/// Values accepted by the `include` query parameter.
+pub(crate) const INCLUDE: &[&str] = &["stats"];
+
+/// Values accepted by the `format` query parameter of `list`.
+pub(crate) const FORMATS: &[&str] = &[
+    "json",
+    "dot",
+    "cyclonedx",
+    "cyclonedx-xml",
+    "spdx",
+    "spdx-tag-value",
+    "tree",
+    "mermaid",
+    "plantuml",
+    "svg",
+    "csv",
+    "ndjson",
+];
+
+/// Values accepted by the `format` query parameter of `diff`.
+pub(crate) const DIFF_FORMATS: &[&str] = &["json", "text", "mermaid", "plantuml"];
+
 #[get("")]
 pub(crate) async fn list(
+    request: HttpRequest,
     database_pool: web::Data<mysql::MySqlPool>,
     http_client: web::Data<reqwest::Client>,
     redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
     query_parameters: web::Query<models::ListQueryParams>,
 ) -> impl Responder {
     // request
-    let name = match &query_parameters.name {
-        Some(name) => name,
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &query_parameters.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let rules = match rules(&query_parameters) {
+        Ok(rules) => rules,
+        Err(response) => return response,
+    };
+
+    let include_stats = match included("stats", &query_parameters.include) {
+        Ok(include_stats) => include_stats,
+        Err(response) => return response,
+    };
+
+    // an explicit `format` overrides the `Accept` header.
+    let format = match &query_parameters.format {
+        Some(format) => format.as_str(),
         None => {
-            return HttpResponse::BadRequest().json(models::ErrorWebDto {
-                status_code: 400,
-                error_message: "name is required".to_owned(),
-            })
+            let accept = request
+                .headers()
+                .get(header::ACCEPT)
+                .and_then(|accept| accept.to_str().ok());
+
+            match negotiation::negotiate(accept) {
+                Some(format) => format,
+                None => {
+                    return not_acceptable(format!(
+                        "accept invalid: supported media types are {}",
+                        negotiation::MEDIA_TYPES
+                            .iter()
+                            .map(|(media_type, _)| *media_type)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                }
+            }
         }
     };
+    if !FORMATS.contains(&format) {
+        return bad_request(format!("format invalid: {:?}", format));
+    }
+    if format != "json" && include_stats {
+        return bad_request(format!("include invalid with format: {:?}", format));
+    }
 
-    let version = match &query_parameters.version {
-        Some(version) => match Version::parse(version) {
-            Ok(version) => version,
-            Err(e) => {
-                return HttpResponse::BadRequest().json(models::ErrorWebDto {
-                    status_code: 400,
-                    error_message: format!("version invalid: {:?}", e),
+    let prefix = query_parameters.prefix.as_deref().unwrap_or("indent");
+    let prefix = match formats::tree::Prefix::parse(prefix) {
+        Some(prefix) => prefix,
+        None => return bad_request(format!("prefix invalid: {:?}", prefix)),
+    };
+
+    // an inverted tree is displayed from a crate anywhere in the graph, so the graph is resolved
+    // in full and `depth` only bounds the display.
+    let depth = if format == "tree" && query_parameters.invert.is_some() {
+        None
+    } else {
+        query_parameters.depth
+    };
+
+    // csv and ndjson are written as crates get resolved, instead of once the graph is complete.
+    if format == "csv" || format == "ndjson" {
+        // data
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let streamed_rules = rules.clone();
+
+        actix_web::rt::spawn(async move {
+            // the outcome is reported to the receiver as an event.
+            let _ = Data::new(
+                database_pool.get_ref(),
+                http_client.get_ref(),
+                redis_pool.get_ref(),
+                limiter.get_ref(),
+                memory_cache.get_ref(),
+                cache_config.get_ref(),
+            )
+            .with_events(sender)
+            .without_complete_event()
+            .with_rules(streamed_rules)
+            .get_dependency_graph(name, version, depth)
+            .await;
+        });
+
+        // response
+        let format = format.to_owned();
+        let mut layer = 0;
+        let csv_header = if format == "csv" {
+            formats::csv::HEADER
+        } else {
+            ""
+        };
+
+        return vary_accept(
+            HttpResponse::Ok()
+                .content_type(if format == "csv" {
+                    "text/csv; charset=utf-8; header=present"
+                } else {
+                    "application/x-ndjson"
                 })
+                .streaming(
+                    futures::stream::once(futures::future::ready(Ok(web::Bytes::from(csv_header))))
+                        .chain(receiver.filter_map(move |event| {
+                            // crates are reported layer by layer, after the layer itself.
+                            if let ResolutionEvent::Layer { depth, .. } = &event {
+                                layer = *depth;
+                            }
+                            futures::future::ready(streamed_chunk(&format, &rules, layer, &event))
+                        })),
+                ),
+        );
+    }
+
+    // data
+    let data = Data::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    )
+    .with_rules(rules.clone());
+
+    // response
+    if include_stats {
+        let result = get_dependency_graph_with_sources(
+            data,
+            name.to_owned(),
+            version.to_owned(),
+            query_parameters.depth,
+        )
+        .await;
+
+        return match result {
+            Ok((c, sources)) => {
+                vary_accept(HttpResponse::Ok().json(models::CratesWithStatsWebDto {
+                    crates: models::CrateWebDto::annotated_with_rules(&name, &version, &c, &rules),
+                    stats: models::StatsWebDto::transform(
+                        &graph::stats(&name, &version, &c),
+                        &sources,
+                    ),
+                }))
             }
-        },
-        None => {
-            return HttpResponse::BadRequest().json(models::ErrorWebDto {
-                status_code: 400,
-                error_message: "version is required".to_owned(),
-            })
+            Err(e) => internal_server_error(e),
+        };
+    }
+
+    let result = data
+        .get_dependency_graph(name.to_owned(), version.to_owned(), depth)
+        .await;
+
+    let mut c = match result {
+        Ok(c) => c,
+        Err(e) => return internal_server_error(e),
+    };
+
+    // metadata is only looked up for the formats describing it.
+    if matches!(
+        format,
+        "cyclonedx" | "cyclonedx-xml" | "spdx" | "spdx-tag-value"
+    ) {
+        if let Err(e) = data.fill_metadata(&mut c).await {
+            return internal_server_error(e);
         }
+    }
+
+    if let Some(invert) = &query_parameters.invert {
+        if format == "tree" && !c.iter().any(|c| &c.name == invert) {
+            return bad_request(format!("invert invalid: {:?} is not in the graph", invert));
+        }
+    }
+
+    let response = match format {
+        "dot" => HttpResponse::Ok()
+            .content_type("text/vnd.graphviz; charset=utf-8")
+            .body(formats::dot::graph(
+                &name,
+                &version,
+                &c,
+                &rules,
+                query_parameters.cluster.unwrap_or_default(),
+            )),
+        "cyclonedx" => HttpResponse::Ok()
+            .content_type("application/vnd.cyclonedx+json; version=1.4")
+            .json(formats::cyclonedx::bom(&name, &version, &c)),
+        "cyclonedx-xml" => HttpResponse::Ok()
+            .content_type("application/vnd.cyclonedx+xml; version=1.4")
+            .body(formats::cyclonedx::xml(&formats::cyclonedx::bom(
+                &name, &version, &c,
+            ))),
+        "spdx" => HttpResponse::Ok()
+            .content_type("application/spdx+json")
+            .json(formats::spdx::document(&name, &version, &c, &now())),
+        "spdx-tag-value" => HttpResponse::Ok()
+            .content_type("text/spdx; charset=utf-8")
+            .body(formats::spdx::tag_value(&formats::spdx::document(
+                &name,
+                &version,
+                &c,
+                &now(),
+            ))),
+        "tree" => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(formats::tree::tree(
+                &name,
+                &version,
+                &c,
+                &formats::tree::TreeOptions {
+                    invert: query_parameters.invert.to_owned(),
+                    depth: query_parameters.depth,
+                    prefix,
+                },
+            )),
+        "mermaid" => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(formats::mermaid::render(&formats::diagram::graph(
+                &name,
+                &version,
+                &c,
+                query_parameters.collapse_leaves.unwrap_or_default(),
+            ))),
+        "plantuml" => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(formats::plantuml::render(&formats::diagram::graph(
+                &name,
+                &version,
+                &c,
+                query_parameters.collapse_leaves.unwrap_or_default(),
+            ))),
+        "svg" => HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .body(formats::svg::graph(&name, &version, &c)),
+        _ => HttpResponse::Ok().json(models::CrateWebDto::annotated_with_rules(
+            &name, &version, &c, &rules,
+        )),
+    };
+
+    vary_accept(response)
+}
+
+#[get("/stats")]
+pub(crate) async fn stats(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::ListQueryParams>,
+) -> impl Responder {
+    // request
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &query_parameters.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let rules = match rules(&query_parameters) {
+        Ok(rules) => rules,
+        Err(response) => return response,
+    };
+
+    // data
+    let result = get_dependency_graph_with_sources(
+        Data::new(
+            database_pool.get_ref(),
+            http_client.get_ref(),
+            redis_pool.get_ref(),
+            limiter.get_ref(),
+            memory_cache.get_ref(),
+            cache_config.get_ref(),
+        )
+        .with_rules(rules),
+        name.to_owned(),
+        version.to_owned(),
+        query_parameters.depth,
+    )
+    .await;
+
+    // response
+    match result {
+        Ok((c, sources)) => HttpResponse::Ok().json(models::StatsWebDto::transform(
+            &graph::stats(&name, &version, &c),
+            &sources,
+        )),
+        Err(e) => internal_server_error(e),
+    }
+}
+
+#[get("/events")]
+pub(crate) async fn events(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::ListQueryParams>,
+) -> impl Responder {
+    // request
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &query_parameters.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let rules = match rules(&query_parameters) {
+        Ok(rules) => rules,
+        Err(response) => return response,
+    };
+
+    let depth = query_parameters.depth;
+
+    // data
+    let (sender, receiver) = futures::channel::mpsc::unbounded();
+    let (root_name, root_version) = (name.to_owned(), version.to_owned());
+    let events_rules = rules.clone();
+
+    actix_web::rt::spawn(async move {
+        // the outcome is reported to the receiver as an event.
+        let _ = Data::new(
+            database_pool.get_ref(),
+            http_client.get_ref(),
+            redis_pool.get_ref(),
+            limiter.get_ref(),
+            memory_cache.get_ref(),
+            cache_config.get_ref(),
+        )
+        .with_events(sender)
+        .with_rules(events_rules)
+        .get_dependency_graph(name, version, depth)
+        .await;
+    });
+
+    // response
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header("cache-control", "no-cache")
+        .streaming(receiver.map(move |event| {
+            Ok::<_, actix_web::Error>(web::Bytes::from(server_sent_event(
+                &root_name,
+                &root_version,
+                &rules,
+                &event,
+            )))
+        }))
+}
+
+#[get("/duplicates")]
+pub(crate) async fn duplicates(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::ListQueryParams>,
+) -> impl Responder {
+    // request
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &query_parameters.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let rules = match rules(&query_parameters) {
+        Ok(rules) => rules,
+        Err(response) => return response,
     };
 
     // data
@@ -45,23 +443,380 @@ pub(crate) async fn list(
         database_pool.get_ref(),
         http_client.get_ref(),
         redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
     )
-    .get_dependency_graph(name.to_owned(), version.to_owned())
+    .with_rules(rules)
+    .get_dependency_graph(name, version, query_parameters.depth)
     .await;
 
     // response
     match result {
         Ok(c) => HttpResponse::Ok().json(
-            c.iter()
-                .map(models::CrateWebDto::transform)
+            graph::duplicates(&c)
+                .iter()
+                .map(models::DuplicateWebDto::transform)
                 .collect::<Vec<_>>(),
         ),
-        Err(e) => {
-            log::error!("{}", e);
-            HttpResponse::InternalServerError().json(models::ErrorWebDto {
-                status_code: 500,
-                error_message: e,
+        Err(e) => internal_server_error(e),
+    }
+}
+
+#[get("/order")]
+pub(crate) async fn order(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::ListQueryParams>,
+) -> impl Responder {
+    // request
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &query_parameters.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let rules = match rules(&query_parameters) {
+        Ok(rules) => rules,
+        Err(response) => return response,
+    };
+
+    // data
+    let result = Data::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    )
+    .with_rules(rules)
+    .get_dependency_graph(name, version, query_parameters.depth)
+    .await;
+
+    // response
+    match result {
+        Ok(c) => match graph::build_order(&c) {
+            Ok(levels) => HttpResponse::Ok().json(models::OrderWebDto::transform(&levels)),
+            Err(cycle) => unprocessable_entity(cycle.describe()),
+        },
+        Err(e) => internal_server_error(e),
+    }
+}
+
+#[post("/batch")]
+pub(crate) async fn batch(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    request: web::Json<models::BatchRequest>,
+) -> impl Responder {
+    // request
+    let mut roots = Vec::new();
+
+    for (index, root) in request.roots.iter().enumerate() {
+        let name = match required(&format!("roots[{}].name", index), &root.name) {
+            Ok(name) => name,
+            Err(response) => return response,
+        };
+
+        let version = match required_version(&format!("roots[{}].version", index), &root.version) {
+            Ok(version) => version,
+            Err(response) => return response,
+        };
+
+        roots.push((name, version));
+    }
+
+    // data
+    let result = Data::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    )
+    .get_dependency_graphs(roots.to_owned())
+    .await;
+
+    // response
+    match result {
+        Ok(graphs) => HttpResponse::Ok().json(models::BatchWebDto {
+            merged: models::CrateWebDto::annotated_from(&roots, &graph::merge(&graphs)),
+            graphs: roots
+                .iter()
+                .zip(graphs.iter())
+                .map(|((name, version), crates)| models::BatchGraphWebDto {
+                    name: name.to_owned(),
+                    version: version.to_string(),
+                    crates: models::CrateWebDto::annotated(name, version, crates),
+                })
+                .collect(),
+        }),
+        Err(e) => internal_server_error(e),
+    }
+}
+
+#[get("/diff")]
+pub(crate) async fn diff(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    query_parameters: web::Query<models::DiffQueryParams>,
+) -> impl Responder {
+    // request
+    let name = match required("name", &query_parameters.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let from = match required_version("from", &query_parameters.from) {
+        Ok(from) => from,
+        Err(response) => return response,
+    };
+
+    let to = match required_version("to", &query_parameters.to) {
+        Ok(to) => to,
+        Err(response) => return response,
+    };
+
+    let format = query_parameters.format.as_deref().unwrap_or("json");
+    if !DIFF_FORMATS.contains(&format) {
+        return bad_request(format!("format invalid: {:?}", format));
+    }
+
+    // data
+    let data = Data::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    );
+
+    // both sides are bounded alike, so crates beyond `depth` are not reported as removed.
+    let result = futures::future::try_join(
+        data.get_dependency_graph(name.to_owned(), from.to_owned(), query_parameters.depth),
+        data.get_dependency_graph(name.to_owned(), to.to_owned(), query_parameters.depth),
+    )
+    .await;
+
+    // response
+    match result {
+        Ok((from_crates, to_crates)) => {
+            let d = graph::diff(&from_crates, &to_crates);
+
+            match format {
+                "text" => HttpResponse::Ok()
+                    .content_type("text/plain; charset=utf-8")
+                    .body(formats::text::diff(&name, &from, &to, &d)),
+                "mermaid" => HttpResponse::Ok()
+                    .content_type("text/plain; charset=utf-8")
+                    .body(formats::mermaid::render(&formats::diagram::diff(&d))),
+                "plantuml" => HttpResponse::Ok()
+                    .content_type("text/plain; charset=utf-8")
+                    .body(formats::plantuml::render(&formats::diagram::diff(&d))),
+                _ => HttpResponse::Ok().json(models::DiffWebDto::transform(&name, &from, &to, &d)),
+            }
+        }
+        Err(e) => internal_server_error(e),
+    }
+}
+
+/// Marks a response as negotiated on `Accept`, so caches keep one representation per media type.
+fn vary_accept(mut response: HttpResponse) -> HttpResponse {
+    response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
+/// Renders a crate resolved at `depth` as a chunk of a streamed representation, other events
+/// having none. A failed resolution ends the stream with an error, so the response is visibly
+/// truncated.
+fn streamed_chunk(
+    format: &str,
+    rules: &Rules,
+    depth: usize,
+    event: &ResolutionEvent,
+) -> Option<Result<web::Bytes, actix_web::Error>> {
+    let fn_name = "streamed_chunk";
+
+    match event {
+        ResolutionEvent::Node(c, _) => {
+            let c = rules.apply(c);
+
+            let chunk = if format == "csv" {
+                Ok(formats::csv::rows(&c))
+            } else {
+                serde_json::to_string(&models::CrateWebDto::streamed(&c, depth, rules))
+                    .map(|line| line + "\n")
+                    .map_err(|e| {
+                        log::error!("{}: error {:?}", fn_name, e);
+                        format!("{}: error {:?}", fn_name, e)
+                    })
+            };
+
+            Some(
+                chunk
+                    .map(web::Bytes::from)
+                    .map_err(actix_web::error::ErrorInternalServerError),
+            )
+        }
+        ResolutionEvent::Error(e) => {
+            log::error!("{}: resolution error {:?}", fn_name, e);
+            Some(Err(actix_web::error::ErrorInternalServerError(
+                e.to_owned(),
+            )))
+        }
+        ResolutionEvent::Layer { .. } | ResolutionEvent::Complete(_) => None,
+    }
+}
+
+/// Resolves the dependency graph, recording where every crate was resolved from.
+async fn get_dependency_graph_with_sources(
+    data: Data<'_>,
+    name: String,
+    version: Version,
+    depth: Option<usize>,
+) -> Result<(Vec<Crate>, Vec<Source>), String> {
+    let (sender, receiver) = futures::channel::mpsc::unbounded();
+
+    let data = data.with_events(sender).without_complete_event();
+    let result = data.get_dependency_graph(name, version, depth).await;
+
+    // closes the event stream.
+    drop(data);
+
+    let sources = receiver
+        .filter_map(|event| {
+            futures::future::ready(match event {
+                ResolutionEvent::Node(_, source) => Some(source),
+                _ => None,
             })
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+    result.map(|c| (c, sources))
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Builds the traversal rules from the `exclude` and `prune` query parameters.
+fn rules(query_parameters: &models::ListQueryParams) -> Result<Rules, HttpResponse> {
+    Ok(Rules::new(
+        patterns("exclude", &query_parameters.exclude)?,
+        patterns("prune", &query_parameters.prune)?,
+    ))
+}
+
+/// Splits comma separated crate name patterns, allowing `*` and `?` wildcards.
+fn patterns(key: &str, value: &Option<String>) -> Result<Vec<String>, HttpResponse> {
+    let mut results = Vec::new();
+
+    for pattern in value.iter().flat_map(|value| value.split(',')) {
+        let pattern = pattern.trim();
+
+        if pattern.is_empty()
+            || !pattern
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_*?".contains(c))
+        {
+            return Err(bad_request(format!("{} invalid: {:?}", key, pattern)));
+        }
+
+        results.push(pattern.to_owned());
+    }
+
+    Ok(results)
+}
+
+/// Whether `value` is one of the comma separated values of the `include` query parameter.
+fn included(value: &str, include: &Option<String>) -> Result<bool, HttpResponse> {
+    let mut included = false;
+
+    for item in include.iter().flat_map(|include| include.split(',')) {
+        let item = item.trim();
+
+        if !INCLUDE.contains(&item) {
+            return Err(bad_request(format!("include invalid: {:?}", item)));
         }
+
+        included |= item == value;
     }
+
+    Ok(included)
+}
+
+fn server_sent_event(
+    name: &str,
+    version: &Version,
+    rules: &Rules,
+    event: &ResolutionEvent,
+) -> String {
+    let fn_name = "server_sent_event";
+
+    let (event_name, data) = match event {
+        ResolutionEvent::Layer { depth, crates } => (
+            "layer",
+            serde_json::to_string(&models::LayerEventWebDto {
+                depth: *depth,
+                crates: *crates,
+            }),
+        ),
+        ResolutionEvent::Node(c, source) => (
+            "node",
+            serde_json::to_string(&models::NodeEventWebDto::transform(c, *source)),
+        ),
+        ResolutionEvent::Error(e) => (
+            "error",
+            serde_json::to_string(&models::ErrorWebDto {
+                status_code: 500,
+                error_message: e.to_owned(),
+            }),
+        ),
+        ResolutionEvent::Complete(crates) => (
+            "complete",
+            serde_json::to_string(&models::CrateWebDto::annotated_with_rules(
+                name, version, crates, rules,
+            )),
+        ),
+    };
+
+    let (event_name, data) = match data {
+        Ok(data) => (event_name, data),
+        Err(error) => {
+            log::error!("{}: error={:?}", fn_name, error);
+            (
+                "error",
+                // an error of plain fields always serialises.
+                serde_json::to_string(&models::ErrorWebDto {
+                    status_code: 500,
+                    error_message: format!("{}: error={:?}", fn_name, error),
+                })
+                .unwrap_or_default(),
+            )
+        }
+    };
+
+    format!("event: {}\ndata: {}\n\n", event_name, data)
 }
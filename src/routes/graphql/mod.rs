@@ -0,0 +1,8 @@
+mod routes;
+
+use actix_web::web::ServiceConfig;
+use routes::{execute, playground};
+
+pub(crate) fn configure(service_config: &mut ServiceConfig) {
+    service_config.service(execute).service(playground);
+}
@@ -0,0 +1,16 @@
+use crate::graphql::CrateSchema;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
+use async_graphql_actix_web::{Request, Response};
+
+#[post("")]
+pub(crate) async fn execute(schema: web::Data<CrateSchema>, request: Request) -> Response {
+    schema.execute(request.into_inner()).await.into()
+}
+
+#[get("")]
+pub(crate) async fn playground() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+}
@@ -1,4 +1,4 @@
-mod models;
+pub(crate) mod models;
 mod routes;
 
 use actix_web::web::ServiceConfig;
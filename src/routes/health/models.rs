@@ -1,6 +1,10 @@
 use crate::health::Health;
 use crate::health::HealthCheck;
 use crate::health::HealthStatus;
+use crate::routes::openapi::{date_time, enumeration};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::HashMap;
 
@@ -9,9 +13,11 @@ pub(crate) enum HealthResponseStatus {
     InternalServerError(HealthResponse),
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "Health")]
 pub(crate) struct HealthResponse {
     #[serde(rename = "status")]
+    #[schemars(schema_with = "status_schema")]
     pub(crate) status: String,
 
     #[serde(skip_serializing_if = "Option::is_none", rename = "version")]
@@ -32,6 +38,7 @@ pub(crate) struct HealthResponse {
     #[serde(skip_serializing_if = "Option::is_none", rename = "description")]
     pub(crate) description: Option<String>,
 
+    /// Checks by component name.
     #[serde(skip_serializing_if = "Option::is_none", rename = "checks")]
     pub(crate) checks: Option<HashMap<String, Vec<HealthCheckResponse>>>,
 
@@ -39,7 +46,8 @@ pub(crate) struct HealthResponse {
     pub(crate) links: Option<HashMap<String, String>>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "HealthCheck")]
 pub(crate) struct HealthCheckResponse {
     #[serde(skip_serializing_if = "Option::is_none", rename = "componentId")]
     pub(crate) component_id: Option<String>,
@@ -54,12 +62,14 @@ pub(crate) struct HealthCheckResponse {
     pub(crate) observed_unit: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none", rename = "status")]
+    #[schemars(schema_with = "status_schema")]
     pub(crate) status: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none", rename = "affectedEndpoints")]
     pub(crate) affected_endpoints: Option<Vec<String>>,
 
     #[serde(skip_serializing_if = "Option::is_none", rename = "time")]
+    #[schemars(schema_with = "date_time")]
     pub(crate) time: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none", rename = "output")]
@@ -72,6 +82,10 @@ pub(crate) struct HealthCheckResponse {
     pub(crate) additional_keys: Option<HashMap<String, String>>,
 }
 
+fn status_schema(_: &mut SchemaGenerator) -> Schema {
+    enumeration(&["pass", "fail", "warn"])
+}
+
 impl HealthResponse {
     pub(crate) fn from(health: &Health) -> HealthResponseStatus {
         let response = HealthResponse {
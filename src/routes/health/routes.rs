@@ -1,3 +1,5 @@
+use crate::cache::MemoryCache;
+use crate::concurrency::Limiter;
 use crate::health::HealthChecker;
 use crate::routes::health::models::HealthResponse;
 use crate::routes::health::models::HealthResponseStatus;
@@ -12,11 +14,15 @@ pub(crate) async fn get(
     database_pool: web::Data<mysql::MySqlPool>,
     http_client_pool: web::Data<reqwest::Client>,
     redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
 ) -> impl Responder {
     let health = HealthChecker::new(
         database_pool.get_ref(),
         http_client_pool.get_ref(),
         redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
     )
     .check()
     .await;
@@ -0,0 +1,61 @@
+use crate::domain::{Job, JobStatus};
+use crate::routes::dependency::models::CrateWebDto;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub(crate) struct DependencyJobRequest {
+    pub(crate) name: Option<String>,
+    pub(crate) version: Option<String>,
+    pub(crate) depth: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct JobWebDto {
+    id: String,
+    status: String,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    depth: Option<usize>,
+    progress: JobProgressWebDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Vec<CrateWebDto>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct JobProgressWebDto {
+    depth: usize,
+    crates: usize,
+}
+
+impl JobWebDto {
+    pub(crate) fn transform(job: &Job) -> Self {
+        Self {
+            id: job.id.clone(),
+            status: match job.status {
+                JobStatus::Queued => "queued".to_owned(),
+                JobStatus::Running => "running".to_owned(),
+                JobStatus::Completed => "completed".to_owned(),
+                JobStatus::Failed => "failed".to_owned(),
+            },
+            name: job.name.clone(),
+            version: job.version.to_string(),
+            depth: job.depth,
+            progress: JobProgressWebDto {
+                depth: job.progress.depth,
+                crates: job.progress.crates,
+            },
+            result: job
+                .result
+                .as_ref()
+                .map(|crates| CrateWebDto::annotated(&job.name, &job.version, crates)),
+            error: job.error.clone(),
+            created_at: job.created_at.clone(),
+            updated_at: job.updated_at.clone(),
+        }
+    }
+}
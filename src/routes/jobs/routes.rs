@@ -0,0 +1,108 @@
+use crate::cache::{CacheConfig, MemoryCache};
+use crate::concurrency::Limiter;
+use crate::jobs::Jobs;
+use crate::routes::dependency::models::ErrorWebDto;
+use crate::routes::jobs::models;
+use crate::routes::{internal_server_error, required, required_version};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use sqlx::mysql;
+
+#[post("/dependency")]
+pub(crate) async fn submit(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    request: web::Json<models::DependencyJobRequest>,
+) -> impl Responder {
+    // request
+    let name = match required("name", &request.name) {
+        Ok(name) => name,
+        Err(response) => return response,
+    };
+
+    let version = match required_version("version", &request.version) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    // data
+    let result = Jobs::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    )
+    .submit(name, version, request.depth)
+    .await;
+
+    let job = match result {
+        Ok(job) => job,
+        Err(e) => return internal_server_error(e),
+    };
+
+    let location = format!("/jobs/{}", job.id);
+    let response = models::JobWebDto::transform(&job);
+
+    actix_web::rt::spawn(async move {
+        let result = Jobs::new(
+            database_pool.get_ref(),
+            http_client.get_ref(),
+            redis_pool.get_ref(),
+            limiter.get_ref(),
+            memory_cache.get_ref(),
+            cache_config.get_ref(),
+        )
+        .execute(job)
+        .await;
+
+        if let Err(e) = result {
+            log::error!("{}", e);
+        }
+    });
+
+    // response
+    HttpResponse::Accepted()
+        .header("location", location)
+        .json(response)
+}
+
+#[get("/{id}")]
+pub(crate) async fn get(
+    database_pool: web::Data<mysql::MySqlPool>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<redis::aio::MultiplexedConnection>,
+    limiter: web::Data<Limiter>,
+    memory_cache: web::Data<MemoryCache>,
+    cache_config: web::Data<CacheConfig>,
+    id: web::Path<String>,
+) -> impl Responder {
+    // request
+    let id = id.into_inner();
+
+    // data
+    let result = Jobs::new(
+        database_pool.get_ref(),
+        http_client.get_ref(),
+        redis_pool.get_ref(),
+        limiter.get_ref(),
+        memory_cache.get_ref(),
+        cache_config.get_ref(),
+    )
+    .get(&id)
+    .await;
+
+    // response
+    match result {
+        Ok(Some(job)) => HttpResponse::Ok().json(models::JobWebDto::transform(&job)),
+        Ok(None) => HttpResponse::NotFound().json(ErrorWebDto {
+            status_code: 404,
+            error_message: format!("job not found: {}", id),
+        }),
+        Err(e) => internal_server_error(e),
+    }
+}
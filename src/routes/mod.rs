@@ -1,10 +1,154 @@
+mod cache;
 mod dependency;
+mod graphql;
 mod health;
+mod jobs;
+mod openapi;
+mod ui;
 
-use actix_web::{web, web::ServiceConfig};
+use crate::routes::dependency::models::ErrorWebDto;
+use actix_web::http::header;
+use actix_web::{web, web::ServiceConfig, HttpRequest, HttpResponse};
+use semver::Version;
+
+/// Settings of the endpoints meant for operators.
+pub(crate) struct AdminConfig {
+    /// Bearer token required by the admin endpoints, which are disabled without one.
+    pub(crate) token: Option<String>,
+}
 
 pub(crate) fn configure(service_config: &mut ServiceConfig) {
     service_config
+        .service(web::scope("/cache").configure(cache::configure))
         .service(web::scope("/dependency").configure(dependency::configure))
-        .service(web::scope("/health").configure(health::configure));
+        .service(web::scope("/graphql").configure(graphql::configure))
+        .service(web::scope("/health").configure(health::configure))
+        .service(web::scope("/jobs").configure(jobs::configure))
+        .service(web::scope("/openapi.json").configure(openapi::configure))
+        .service(web::scope("/ui").configure(ui::configure));
+}
+
+fn required(key: &str, value: &Option<String>) -> Result<String, HttpResponse> {
+    match value {
+        Some(value) => Ok(value.to_owned()),
+        None => Err(bad_request(format!("{} is required", key))),
+    }
+}
+
+fn required_version(key: &str, value: &Option<String>) -> Result<Version, HttpResponse> {
+    Version::parse(&required(key, value)?)
+        .map_err(|e| bad_request(format!("{} invalid: {:?}", key, e)))
+}
+
+/// Requires the admin token as a bearer token.
+fn authorize(request: &HttpRequest, admin_config: &AdminConfig) -> Result<(), HttpResponse> {
+    let token = match &admin_config.token {
+        Some(token) => token,
+        None => return Err(forbidden("admin endpoints disabled".to_owned())),
+    };
+
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map_or(false, |bearer| {
+            constant_time_eq(bearer.as_bytes(), token.as_bytes())
+        });
+
+    if authorized {
+        Ok(())
+    } else {
+        Err(unauthorized("admin token required".to_owned()))
+    }
+}
+
+/// Compares in a time independent of where the bytes differ, not to leak the token by timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn bad_request(error_message: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(ErrorWebDto {
+        status_code: 400,
+        error_message,
+    })
+}
+
+fn unauthorized(error_message: String) -> HttpResponse {
+    HttpResponse::Unauthorized()
+        .header(header::WWW_AUTHENTICATE, "Bearer")
+        .json(ErrorWebDto {
+            status_code: 401,
+            error_message,
+        })
+}
+
+fn forbidden(error_message: String) -> HttpResponse {
+    HttpResponse::Forbidden().json(ErrorWebDto {
+        status_code: 403,
+        error_message,
+    })
+}
+
+fn not_acceptable(error_message: String) -> HttpResponse {
+    HttpResponse::NotAcceptable().json(ErrorWebDto {
+        status_code: 406,
+        error_message,
+    })
+}
+
+fn unprocessable_entity(error_message: String) -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(ErrorWebDto {
+        status_code: 422,
+        error_message,
+    })
+}
+
+fn internal_server_error(error_message: String) -> HttpResponse {
+    log::error!("{}", error_message);
+    HttpResponse::InternalServerError().json(ErrorWebDto {
+        status_code: 500,
+        error_message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test;
+
+    #[test]
+    fn authorize_requires_the_admin_token() {
+        let admin_config = AdminConfig {
+            token: Some("secret".to_owned()),
+        };
+        let request = |authorization: Option<&str>| {
+            let request = test::TestRequest::default();
+            match authorization {
+                Some(authorization) => request.header(header::AUTHORIZATION, authorization),
+                None => request,
+            }
+            .to_http_request()
+        };
+
+        assert!(authorize(&request(Some("Bearer secret")), &admin_config).is_ok());
+
+        for authorization in &[
+            None,
+            Some("Bearer secreT"),
+            Some("Bearer secrets"),
+            Some("secret"),
+        ] {
+            let response = authorize(&request(*authorization), &admin_config).unwrap_err();
+            assert_eq!(response.status(), 401);
+        }
+
+        let response = authorize(
+            &request(Some("Bearer secret")),
+            &AdminConfig { token: None },
+        )
+        .unwrap_err();
+        assert_eq!(response.status(), 403);
+    }
 }
@@ -0,0 +1,575 @@
+mod routes;
+
+use crate::routes::dependency::models::{
+    BatchRequest, BatchWebDto, CrateWebDto, CratesWithStatsWebDto, DiffQueryParams, DiffWebDto,
+    DuplicateWebDto, ErrorWebDto, ListQueryParams, OrderWebDto, StatsWebDto,
+};
+use crate::routes::dependency::negotiation::MEDIA_TYPES;
+use crate::routes::health::models::HealthResponse;
+use actix_web::web::ServiceConfig;
+use routes::get;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+pub(crate) fn configure(service_config: &mut ServiceConfig) {
+    service_config.service(get);
+}
+
+/// OpenAPI 3 document of the dependency and health endpoints, the schemas being derived from
+/// the DTOs they are implemented with.
+pub(crate) fn document() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let crates = schema::<Vec<CrateWebDto>>(&mut generator);
+    let crates_with_stats = schema::<CratesWithStatsWebDto>(&mut generator);
+    let stats = schema::<StatsWebDto>(&mut generator);
+    let duplicates = schema::<Vec<DuplicateWebDto>>(&mut generator);
+    let order = schema::<OrderWebDto>(&mut generator);
+    let batch_request = schema::<BatchRequest>(&mut generator);
+    let batch = schema::<BatchWebDto>(&mut generator);
+    let diff = schema::<DiffWebDto>(&mut generator);
+    let error = schema::<ErrorWebDto>(&mut generator);
+    let health = schema::<HealthResponse>(&mut generator);
+
+    let body = |description: &str, schema: &Value| {
+        json!({
+            "description": description,
+            "content": { "application/json": { "schema": schema } }
+        })
+    };
+    let text = |description: &str, media_type: &str| {
+        json!({
+            "description": description,
+            "content": { media_type: { "schema": { "type": "string" } } }
+        })
+    };
+    let error = |description: &str| body(description, &error);
+    let health = |description: &str| {
+        json!({
+            "description": description,
+            "content": { "application/health+json": { "schema": health } }
+        })
+    };
+
+    // every representation of the graph but JSON is a document of its own format.
+    let mut representations = Map::new();
+    for (media_type, format) in MEDIA_TYPES {
+        representations.insert(
+            (*media_type).to_owned(),
+            if *format == "json" {
+                json!({ "schema": { "oneOf": [crates, crates_with_stats] } })
+            } else {
+                json!({ "schema": { "type": "string" } })
+            },
+        );
+    }
+
+    let list_parameters = parameters::<ListQueryParams>(&["name", "version"]);
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Resolves the dependency graphs of crates published on crates.io."
+        },
+        "paths": {
+            "/dependency": {
+                "get": {
+                    "operationId": "listDependencies",
+                    "summary": "Gets dependencies for crate+version",
+                    "parameters": list_parameters,
+                    "responses": {
+                        "200": {
+                            "description": "The dependency graph, every crate annotated with its depth and dependents.",
+                            "content": representations
+                        },
+                        "400": error("A parameter is missing or invalid."),
+                        "406": error("The `Accept` header lists no supported media type."),
+                        "500": error("The dependency graph could not be resolved.")
+                    }
+                }
+            },
+            "/dependency/stats": {
+                "get": {
+                    "operationId": "getDependencyStats",
+                    "summary": "Gets statistics of the dependency graph of crate+version",
+                    "parameters": list_parameters,
+                    "responses": {
+                        "200": body("Statistics of the dependency graph.", &stats),
+                        "400": error("A parameter is missing or invalid."),
+                        "500": error("The dependency graph could not be resolved.")
+                    }
+                }
+            },
+            "/dependency/events": {
+                "get": {
+                    "operationId": "streamDependencyEvents",
+                    "summary": "Streams the progress of resolving the dependency graph of crate+version",
+                    "parameters": list_parameters,
+                    "responses": {
+                        "200": text("Server-sent `layer`, `node`, `complete` and `error` events.", "text/event-stream"),
+                        "400": error("A parameter is missing or invalid.")
+                    }
+                }
+            },
+            "/dependency/duplicates": {
+                "get": {
+                    "operationId": "listDuplicateDependencies",
+                    "summary": "Gets the crates of the dependency graph of crate+version resolved to many versions",
+                    "parameters": list_parameters,
+                    "responses": {
+                        "200": body("Every duplicate crate, with the crates depending on each version.", &duplicates),
+                        "400": error("A parameter is missing or invalid."),
+                        "500": error("The dependency graph could not be resolved.")
+                    }
+                }
+            },
+            "/dependency/order": {
+                "get": {
+                    "operationId": "getBuildOrder",
+                    "summary": "Gets the build order of the dependency graph of crate+version, leaves first",
+                    "parameters": list_parameters,
+                    "responses": {
+                        "200": body("Levels of crates buildable in parallel.", &order),
+                        "400": error("A parameter is missing or invalid."),
+                        "422": error("The dependency graph has a cycle."),
+                        "500": error("The dependency graph could not be resolved.")
+                    }
+                }
+            },
+            "/dependency/batch": {
+                "post": {
+                    "operationId": "batchDependencies",
+                    "summary": "Gets dependencies for many crate+version roots",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": batch_request } }
+                    },
+                    "responses": {
+                        "200": body("The graph of every root, in request order, and the merged graph.", &batch),
+                        "400": error("A root is missing its name or version."),
+                        "500": error("The dependency graphs could not be resolved.")
+                    }
+                }
+            },
+            "/dependency/diff": {
+                "get": {
+                    "operationId": "diffDependencies",
+                    "summary": "Gets the changes to the dependency graph between two versions of a crate",
+                    "parameters": parameters::<DiffQueryParams>(&["name", "from", "to"]),
+                    "responses": {
+                        "200": {
+                            "description": "Added, removed and changed crates and edges.",
+                            "content": {
+                                "application/json": { "schema": diff },
+                                "text/plain": { "schema": { "type": "string" } }
+                            }
+                        },
+                        "400": error("A parameter is missing or invalid."),
+                        "500": error("The dependency graphs could not be resolved.")
+                    }
+                }
+            },
+            "/health": {
+                "get": {
+                    "operationId": "getHealth",
+                    "summary": "Standardized health check",
+                    "responses": {
+                        "200": health("The service passes, or passes with warnings."),
+                        "500": health("The service fails.")
+                    }
+                }
+            },
+            "/openapi.json": {
+                "get": {
+                    "operationId": "getOpenApi",
+                    "summary": "This document",
+                    "responses": {
+                        "200": {
+                            "description": "OpenAPI 3 document.",
+                            "content": { "application/json": { "schema": { "type": "object" } } }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": generator.take_definitions()
+        }
+    })
+}
+
+/// Schema of `T`, a reference to a component when `T` is a struct.
+fn schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Value {
+    json!(generator.subschema_for::<T>())
+}
+
+/// Describes every field of a query DTO as a query parameter.
+fn parameters<T: JsonSchema>(required: &[&str]) -> Vec<Value> {
+    let root = json!(SchemaSettings::openapi3()
+        .into_generator()
+        .into_root_schema_for::<T>());
+
+    root["properties"]
+        .as_object()
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, schema)| {
+                    // every query parameter is optional to deserialize, the handler requiring some.
+                    let mut schema = schema.clone();
+                    let description = schema
+                        .as_object_mut()
+                        .and_then(|s| {
+                            s.remove("nullable");
+                            s.remove("description")
+                        })
+                        .unwrap_or_default();
+
+                    json!({
+                        "name": name,
+                        "in": "query",
+                        "required": required.contains(&name.as_str()),
+                        "description": description,
+                        "schema": schema
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Schema of a string taking one of `values`.
+pub(crate) fn enumeration(values: &[&str]) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(values.iter().map(|v| json!(v)).collect()),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Schema of an RFC 3339 date and time.
+pub(crate) fn date_time(_: &mut SchemaGenerator) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        format: Some("date-time".to_owned()),
+        ..Default::default()
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+    use crate::graph::Rules;
+    use crate::routes::dependency::models::BatchGraphWebDto;
+    use crate::routes::health::models::HealthCheckResponse;
+    use crate::test_support::node;
+    use actix_web::http::Method;
+    use actix_web::{test, web, App};
+    use semver::Version;
+    use std::collections::HashMap;
+
+    #[actix_rt::test]
+    async fn paths_are_routed() {
+        let mut app = test::init_service(App::new().configure(crate::routes::configure)).await;
+
+        let document = document();
+        for (path, item) in document["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                let request = test::TestRequest::default()
+                    .method(Method::from_bytes(method.to_uppercase().as_bytes()).unwrap())
+                    .uri(path)
+                    .to_request();
+                let response = test::call_service(&mut app, request).await;
+
+                // handlers missing their pools fail, unrouted paths are not found.
+                assert_ne!(response.status(), 404, "{} {}", method, path);
+                assert_ne!(response.status(), 405, "{} {}", method, path);
+            }
+        }
+    }
+
+    #[test]
+    fn routes_are_documented() {
+        let document = document();
+
+        for (scope, source) in &[
+            ("/dependency", include_str!("../dependency/routes.rs")),
+            ("/health", include_str!("../health/routes.rs")),
+            ("/openapi.json", include_str!("routes.rs")),
+        ] {
+            let handlers = handlers(source);
+            assert!(!handlers.is_empty(), "{}", scope);
+
+            for (method, path) in handlers {
+                let path = format!("{}{}", scope, path);
+                assert!(
+                    document["paths"][&path][method].is_object(),
+                    "{} {} is not documented",
+                    method,
+                    path
+                );
+            }
+        }
+    }
+
+    /// Method and path of every handler declared in `source`, read from its route attribute.
+    fn handlers(source: &str) -> Vec<(&str, &str)> {
+        source
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim().strip_prefix("#[")?;
+                let (method, path) = line.split_at(line.find("(\"")?);
+                let path = path.strip_prefix("(\"")?.strip_suffix("\")]")?;
+
+                if ["get", "post", "put", "patch", "delete"].contains(&method) {
+                    Some((method, path))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parameters_are_deserialized() {
+        let parameters = parameters::<ListQueryParams>(&["name", "version"]);
+        let query = parameters
+            .iter()
+            .map(|p| {
+                let value = match p["schema"]["type"].as_str().unwrap() {
+                    "integer" => "1",
+                    "boolean" => "true",
+                    _ => "value",
+                };
+                format!("{}={}", p["name"].as_str().unwrap(), value)
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+
+        // every field is destructured, so a new field fails to compile until it is described.
+        let ListQueryParams {
+            name,
+            version,
+            depth,
+            include,
+            exclude,
+            prune,
+            format,
+            cluster,
+            invert,
+            prefix,
+            collapse_leaves,
+        } = web::Query::<ListQueryParams>::from_query(&query)
+            .unwrap()
+            .into_inner();
+
+        assert!(name.is_some());
+        assert!(version.is_some());
+        assert!(depth.is_some());
+        assert!(include.is_some());
+        assert!(exclude.is_some());
+        assert!(prune.is_some());
+        assert!(format.is_some());
+        assert!(cluster.is_some());
+        assert!(invert.is_some());
+        assert!(prefix.is_some());
+        assert!(collapse_leaves.is_some());
+        assert_eq!(parameters.len(), 11);
+        assert!(parameters
+            .iter()
+            .all(|p| p["description"].as_str().map_or(false, |d| !d.is_empty())));
+    }
+
+    #[test]
+    fn schemas_match_dtos() {
+        let document = document();
+        let response = |path: &str, method: &str| {
+            document["paths"][path][method]["responses"]["200"]["content"]["application/json"]
+                ["schema"]
+                .clone()
+        };
+
+        let version = Version::new(1, 0, 0);
+        let crates = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[("c", "2.0.0")]),
+            node("c", "1.0.0", &[]),
+            node("c", "2.0.0", &[]),
+        ];
+        // every crate pruned, so every property of the crates is present.
+        let rules = Rules::new(Vec::new(), vec!["*".to_owned()]);
+        let annotated = CrateWebDto::annotated_with_rules("a", &version, &crates, &rules);
+        let stats = StatsWebDto::transform(
+            &graph::stats("a", &version, &crates),
+            &[
+                crate::data::Source::Cache,
+                crate::data::Source::Database,
+                crate::data::Source::Upstream,
+            ],
+        );
+
+        conforms(
+            &document,
+            &document["components"]["schemas"]["CratesWithStats"],
+            &serde_json::to_value(CratesWithStatsWebDto {
+                crates: annotated,
+                stats,
+            })
+            .unwrap(),
+        );
+
+        let duplicates = graph::duplicates(&crates)
+            .iter()
+            .map(DuplicateWebDto::transform)
+            .collect::<Vec<_>>();
+        conforms(
+            &document,
+            &response("/dependency/duplicates", "get"),
+            &serde_json::to_value(&duplicates).unwrap(),
+        );
+
+        let order = graph::build_order(&crates).unwrap();
+        conforms(
+            &document,
+            &response("/dependency/order", "get"),
+            &serde_json::to_value(OrderWebDto::transform(&order)).unwrap(),
+        );
+
+        conforms(
+            &document,
+            &response("/dependency/batch", "post"),
+            &serde_json::to_value(BatchWebDto {
+                graphs: vec![BatchGraphWebDto {
+                    name: "a".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    crates: CrateWebDto::annotated_with_rules("a", &version, &crates, &rules),
+                }],
+                merged: CrateWebDto::annotated_with_rules("a", &version, &crates, &rules),
+            })
+            .unwrap(),
+        );
+
+        let from = vec![
+            node("a", "1.0.0", &[("b", "1.0.0"), ("c", "1.0.0")]),
+            node("b", "1.0.0", &[]),
+            node("c", "1.0.0", &[]),
+        ];
+        let to = vec![
+            node("a", "2.0.0", &[("b", "2.0.0"), ("d", "1.0.0")]),
+            node("b", "2.0.0", &[]),
+            node("d", "1.0.0", &[]),
+        ];
+        conforms(
+            &document,
+            &response("/dependency/diff", "get"),
+            &serde_json::to_value(DiffWebDto::transform(
+                "a",
+                &version,
+                &Version::new(2, 0, 0),
+                &graph::diff(&from, &to),
+            ))
+            .unwrap(),
+        );
+
+        conforms(
+            &document,
+            &document["components"]["schemas"]["Error"],
+            &serde_json::to_value(ErrorWebDto {
+                status_code: 400,
+                error_message: "name is required".to_owned(),
+            })
+            .unwrap(),
+        );
+
+        let mut keys = HashMap::new();
+        keys.insert("key".to_owned(), "value".to_owned());
+        let mut checks = HashMap::new();
+        checks.insert(
+            "uptime".to_owned(),
+            vec![HealthCheckResponse {
+                component_id: Some("id".to_owned()),
+                component_type: Some("system".to_owned()),
+                observed_value: Some("0".to_owned()),
+                observed_unit: Some("tasks".to_owned()),
+                status: Some("pass".to_owned()),
+                affected_endpoints: Some(vec!["/dependency".to_owned()]),
+                time: Some("2020-10-25T01:28:41Z".to_owned()),
+                output: Some("output".to_owned()),
+                links: Some(keys.clone()),
+                additional_keys: Some(keys.clone()),
+            }],
+        );
+        conforms(
+            &document,
+            &document["components"]["schemas"]["Health"],
+            &serde_json::to_value(HealthResponse {
+                status: "pass".to_owned(),
+                version: Some("0".to_owned()),
+                release_id: Some("0.1.0".to_owned()),
+                notes: Some(vec!["note".to_owned()]),
+                output: Some("output".to_owned()),
+                service_id: Some("id".to_owned()),
+                description: Some("description".to_owned()),
+                checks: Some(checks),
+                links: Some(keys),
+            })
+            .unwrap(),
+        );
+    }
+
+    /// Asserts a fully populated DTO has exactly the properties of its schema, recursively.
+    fn conforms(document: &Value, schema: &Value, value: &Value) {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            return conforms(document, &document["components"]["schemas"][name], value);
+        }
+
+        if let Some(schemas) = schema["allOf"].as_array() {
+            for schema in schemas {
+                conforms(document, schema, value);
+            }
+            return;
+        }
+
+        if let Some(values) = schema["enum"].as_array() {
+            assert!(values.contains(value), "{}", value);
+        }
+
+        match schema["type"].as_str().unwrap() {
+            "object" => {
+                let value = value.as_object().unwrap();
+                if let Some(properties) = schema["properties"].as_object() {
+                    let mut expected = properties.keys().collect::<Vec<_>>();
+                    let mut actual = value.keys().collect::<Vec<_>>();
+                    expected.sort();
+                    actual.sort();
+                    assert_eq!(actual, expected);
+
+                    for (key, property) in properties {
+                        conforms(document, property, &value[key]);
+                    }
+                } else {
+                    for property in value.values() {
+                        conforms(document, &schema["additionalProperties"], property);
+                    }
+                }
+            }
+            "array" => {
+                for item in value.as_array().unwrap() {
+                    conforms(document, &schema["items"], item);
+                }
+            }
+            "string" => assert!(value.is_string(), "{}", value),
+            "integer" => assert!(value.is_u64() || value.is_i64(), "{}", value),
+            "number" => assert!(value.is_number(), "{}", value),
+            "boolean" => assert!(value.is_boolean(), "{}", value),
+            other => panic!("type unsupported: {}", other),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use crate::routes::openapi::document;
+use actix_web::{get, HttpResponse, Responder};
+
+#[get("")]
+pub(crate) async fn get() -> impl Responder {
+    HttpResponse::Ok().json(document())
+}
@@ -0,0 +1,27 @@
+use actix_web::{get, HttpResponse, Responder};
+
+// compiled in, so the binary serves the page without any files alongside it.
+const INDEX: &str = include_str!("static/index.html");
+const SCRIPT: &str = include_str!("static/app.js");
+const STYLE: &str = include_str!("static/app.css");
+
+#[get("")]
+pub(crate) async fn index() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(INDEX)
+}
+
+#[get("/app.js")]
+pub(crate) async fn script() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("application/javascript; charset=utf-8")
+        .body(SCRIPT)
+}
+
+#[get("/app.css")]
+pub(crate) async fn style() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/css; charset=utf-8")
+        .body(STYLE)
+}
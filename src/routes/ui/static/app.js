@@ -0,0 +1,452 @@
+"use strict";
+
+const SVG_NS = "http://www.w3.org/2000/svg";
+const NODE_HEIGHT = 36;
+const LAYER_GAP = 48;
+const NODE_GAP = 16;
+const CHAR_WIDTH = 7;
+const PADDING = 24;
+
+const state = {
+    root: null,
+    // key -> { name, version, depth, dependents, pruned, children, parents }
+    nodes: new Map(),
+    collapsed: new Set(),
+    selected: null,
+    search: "",
+    transform: { x: 0, y: 0, scale: 1 },
+};
+
+const elements = {
+    form: document.getElementById("resolve"),
+    name: document.getElementById("name"),
+    version: document.getElementById("version"),
+    depth: document.getElementById("depth"),
+    search: document.getElementById("search"),
+    status: document.getElementById("status"),
+    graph: document.getElementById("graph"),
+    viewport: document.getElementById("viewport"),
+    details: document.getElementById("details"),
+};
+
+const key = (name, version) => `${name} ${version}`;
+
+function status(message, error) {
+    elements.status.textContent = message;
+    elements.status.classList.toggle("error", Boolean(error));
+}
+
+async function resolve(name, version, depth) {
+    const parameters = new URLSearchParams({ name, version });
+    if (depth !== "") {
+        parameters.set("depth", depth);
+    }
+
+    const response = await fetch(`/dependency?${parameters}`, {
+        headers: { accept: "application/json" },
+    });
+    const body = await response.json();
+
+    if (!response.ok) {
+        throw new Error(body.error_message || response.statusText);
+    }
+
+    return body;
+}
+
+function load(name, version, crates) {
+    state.nodes = new Map();
+    state.collapsed = new Set();
+    state.selected = null;
+    state.root = key(name, version);
+
+    for (const c of crates) {
+        state.nodes.set(key(c.name, c.version), {
+            name: c.name,
+            version: c.version,
+            depth: c.depth,
+            dependents: c.dependents,
+            pruned: Boolean(c.pruned),
+            children: [],
+            parents: [],
+        });
+    }
+
+    for (const c of crates) {
+        const from = key(c.name, c.version);
+        for (const d of c.dependency) {
+            const to = key(d.name, d.version);
+            // dependencies beyond the requested depth are not in the graph.
+            if (state.nodes.has(to)) {
+                state.nodes.get(from).children.push(to);
+                state.nodes.get(to).parents.push(from);
+            }
+        }
+    }
+}
+
+// Crates reachable from the root without going below a collapsed crate, with their distance.
+function visible() {
+    const depths = new Map();
+    if (!state.nodes.has(state.root)) {
+        return depths;
+    }
+
+    depths.set(state.root, 0);
+    const queue = [state.root];
+
+    while (queue.length > 0) {
+        const current = queue.shift();
+        if (state.collapsed.has(current)) {
+            continue;
+        }
+        for (const child of state.nodes.get(current).children) {
+            if (!depths.has(child)) {
+                depths.set(child, depths.get(current) + 1);
+                queue.push(child);
+            }
+        }
+    }
+
+    return depths;
+}
+
+function width(node) {
+    return Math.max(node.name.length, node.version.length + 1) * CHAR_WIDTH + PADDING;
+}
+
+// Layers by distance from the root, ordered by the mean position of their parents.
+function layout(depths) {
+    const layers = [];
+    for (const [k, depth] of depths) {
+        (layers[depth] = layers[depth] || []).push(k);
+    }
+
+    const positions = new Map();
+    for (const layer of layers) {
+        const barycenter = (k) => {
+            const parents = state.nodes.get(k).parents.filter((p) => positions.has(p));
+            return parents.length === 0
+                ? 0
+                : parents.reduce((sum, p) => sum + positions.get(p), 0) / parents.length;
+        };
+        layer.sort((a, b) => barycenter(a) - barycenter(b) || a.localeCompare(b));
+        layer.forEach((k, index) => positions.set(k, index));
+    }
+
+    const layerWidths = layers.map(
+        (layer) =>
+            layer.reduce((sum, k) => sum + width(state.nodes.get(k)), 0) +
+            NODE_GAP * (layer.length - 1)
+    );
+    const contentWidth = Math.max(0, ...layerWidths);
+
+    const boxes = new Map();
+    layers.forEach((layer, depth) => {
+        let left = (contentWidth - layerWidths[depth]) / 2;
+        for (const k of layer) {
+            const w = width(state.nodes.get(k));
+            boxes.set(k, { x: left, y: depth * (NODE_HEIGHT + LAYER_GAP), width: w });
+            left += w + NODE_GAP;
+        }
+    });
+
+    return { boxes, width: contentWidth, height: layers.length * (NODE_HEIGHT + LAYER_GAP) };
+}
+
+// The crates on a path from the root to the target, the target included.
+function ancestors(target, depths) {
+    const result = new Set([target]);
+    const stack = [target];
+
+    while (stack.length > 0) {
+        for (const parent of state.nodes.get(stack.pop()).parents) {
+            if (depths.has(parent) && !state.collapsed.has(parent) && !result.has(parent)) {
+                result.add(parent);
+                stack.push(parent);
+            }
+        }
+    }
+
+    return result;
+}
+
+function element(name, attributes, parent) {
+    const e = document.createElementNS(SVG_NS, name);
+    for (const [attribute, value] of Object.entries(attributes)) {
+        e.setAttribute(attribute, value);
+    }
+    parent.appendChild(e);
+    return e;
+}
+
+function render() {
+    const depths = visible();
+    const { boxes } = layout(depths);
+    const path = state.selected && depths.has(state.selected)
+        ? ancestors(state.selected, depths)
+        : new Set();
+    const search = state.search.toLowerCase();
+
+    elements.viewport.replaceChildren();
+    applyTransform();
+
+    for (const [from, box] of boxes) {
+        if (state.collapsed.has(from)) {
+            continue;
+        }
+        for (const to of state.nodes.get(from).children) {
+            const target = boxes.get(to);
+            const x1 = box.x + box.width / 2;
+            const y1 = box.y + NODE_HEIGHT;
+            const x2 = target.x + target.width / 2;
+            const y2 = target.y;
+            const bend = Math.max(LAYER_GAP / 2, Math.abs(y2 - y1) / 2);
+            const highlighted = path.has(from) && path.has(to);
+
+            element(
+                "path",
+                {
+                    class: highlighted ? "edge highlighted" : "edge",
+                    d: `M ${x1} ${y1} C ${x1} ${y1 + bend} ${x2} ${y2 - bend} ${x2} ${y2}`,
+                    "marker-end": "url(#arrow)",
+                },
+                elements.viewport
+            );
+        }
+    }
+
+    for (const [k, box] of boxes) {
+        const node = state.nodes.get(k);
+        const classes = ["node"];
+        if (k === state.root) classes.push("root");
+        if (node.pruned) classes.push("pruned");
+        if (search && node.name.toLowerCase().includes(search)) classes.push("match");
+        if (path.has(k)) classes.push("highlighted");
+        if (k === state.selected) classes.push("selected");
+
+        const g = element(
+            "g",
+            { class: classes.join(" "), transform: `translate(${box.x}, ${box.y})` },
+            elements.viewport
+        );
+        element("title", {}, g).textContent =
+            `${node.name} ${node.version}\ndepth ${node.depth}, ${node.dependents} dependents`;
+        element("rect", { width: box.width, height: NODE_HEIGHT, rx: 4 }, g);
+        element("text", { x: box.width / 2, y: 15 }, g).textContent = node.name;
+        element("text", { class: "version", x: box.width / 2, y: 29 }, g).textContent =
+            `v${node.version}`;
+
+        if (node.children.length > 0) {
+            element("text", { class: "toggle", x: box.width - 8, y: 15 }, g).textContent =
+                state.collapsed.has(k) ? "+" : "−";
+        }
+
+        g.addEventListener("click", (event) => {
+            event.stopPropagation();
+            select(k);
+        });
+        g.addEventListener("dblclick", (event) => {
+            event.stopPropagation();
+            toggle(k);
+        });
+    }
+
+    renderDetails(depths);
+}
+
+function renderDetails(depths) {
+    const details = elements.details;
+    details.replaceChildren();
+    details.hidden = !state.selected;
+    if (!state.selected) {
+        return;
+    }
+
+    const node = state.nodes.get(state.selected);
+    const add = (tag, text, parent = details) => {
+        const e = document.createElement(tag);
+        if (text !== undefined) e.textContent = text;
+        parent.appendChild(e);
+        return e;
+    };
+    const list = (title, keys) => {
+        add("h3", `${title} (${keys.length})`);
+        const ul = add("ul");
+        for (const k of keys) {
+            const a = add("a", k, add("li", undefined, ul));
+            a.className = "crate";
+            a.addEventListener("click", () => select(k, true));
+        }
+    };
+
+    add("h2", node.name);
+    const link = add("a", `v${node.version} on crates.io`);
+    link.href = `https://crates.io/crates/${encodeURIComponent(node.name)}/${encodeURIComponent(node.version)}`;
+    link.target = "_blank";
+    add("p", `depth ${node.depth}, ${node.dependents} dependents${node.pruned ? ", pruned" : ""}`);
+
+    if (node.children.length > 0) {
+        const button = add("button", state.collapsed.has(state.selected) ? "Expand" : "Collapse");
+        button.addEventListener("click", () => toggle(state.selected));
+    }
+    if (!depths.has(state.selected)) {
+        add("p", "hidden below a collapsed crate");
+    }
+
+    list("dependencies", node.children);
+    list("dependents", node.parents);
+}
+
+function select(k, center) {
+    state.selected = k;
+    render();
+    if (center) {
+        centerOn(k);
+    }
+}
+
+function toggle(k) {
+    if (state.collapsed.has(k)) {
+        state.collapsed.delete(k);
+    } else {
+        state.collapsed.add(k);
+    }
+    render();
+}
+
+function applyTransform() {
+    const { x, y, scale } = state.transform;
+    elements.viewport.setAttribute("transform", `translate(${x}, ${y}) scale(${scale})`);
+}
+
+function fit() {
+    const { width, height } = layout(visible());
+    const bounds = elements.graph.getBoundingClientRect();
+    const scale = Math.min(1, bounds.width / (width + 32), bounds.height / (height + 32));
+
+    state.transform = {
+        x: (bounds.width - width * scale) / 2,
+        y: 16,
+        scale,
+    };
+    applyTransform();
+}
+
+function centerOn(k) {
+    const box = layout(visible()).boxes.get(k);
+    if (!box) {
+        return;
+    }
+
+    const bounds = elements.graph.getBoundingClientRect();
+    const { scale } = state.transform;
+    state.transform.x = bounds.width / 2 - (box.x + box.width / 2) * scale;
+    state.transform.y = bounds.height / 2 - (box.y + NODE_HEIGHT / 2) * scale;
+    applyTransform();
+}
+
+elements.form.addEventListener("submit", async (event) => {
+    event.preventDefault();
+
+    const name = elements.name.value.trim();
+    const version = elements.version.value.trim();
+    const depth = elements.depth.value.trim();
+
+    window.location.hash = new URLSearchParams({ name, version, depth }).toString();
+    status("resolving…");
+
+    try {
+        const crates = await resolve(name, version, depth);
+        load(name, version, crates);
+        elements.search.disabled = false;
+        status(`${crates.length} crates`);
+        render();
+        fit();
+    } catch (error) {
+        status(error.message, true);
+    }
+});
+
+elements.search.addEventListener("input", () => {
+    state.search = elements.search.value.trim();
+    render();
+});
+
+elements.search.addEventListener("keydown", (event) => {
+    if (event.key !== "Enter" || !state.search) {
+        return;
+    }
+
+    const search = state.search.toLowerCase();
+    const depths = visible();
+    const match = [...state.nodes.keys()]
+        .filter((k) => state.nodes.get(k).name.toLowerCase().includes(search))
+        .sort((a, b) => depths.has(b) - depths.has(a) || a.localeCompare(b))[0];
+
+    if (match) {
+        select(match, true);
+    }
+});
+
+// pan by dragging, zoom around the pointer with the wheel.
+let pan = null;
+let panned = false;
+
+elements.graph.addEventListener("mousedown", (event) => {
+    pan = { x: event.clientX - state.transform.x, y: event.clientY - state.transform.y };
+    panned = false;
+    elements.graph.classList.add("panning");
+});
+
+window.addEventListener("mousemove", (event) => {
+    if (pan) {
+        panned = true;
+        state.transform.x = event.clientX - pan.x;
+        state.transform.y = event.clientY - pan.y;
+        applyTransform();
+    }
+});
+
+window.addEventListener("mouseup", () => {
+    pan = null;
+    elements.graph.classList.remove("panning");
+});
+
+elements.graph.addEventListener(
+    "wheel",
+    (event) => {
+        event.preventDefault();
+
+        const bounds = elements.graph.getBoundingClientRect();
+        const pointerX = event.clientX - bounds.left;
+        const pointerY = event.clientY - bounds.top;
+        const { x, y, scale } = state.transform;
+        const next = Math.min(4, Math.max(0.1, scale * Math.exp(-event.deltaY / 500)));
+
+        state.transform = {
+            x: pointerX - ((pointerX - x) * next) / scale,
+            y: pointerY - ((pointerY - y) * next) / scale,
+            scale: next,
+        };
+        applyTransform();
+    },
+    { passive: false }
+);
+
+// clicking the background clears the selection, the end of a drag does not.
+elements.graph.addEventListener("click", () => {
+    if (state.selected && !panned) {
+        state.selected = null;
+        render();
+    }
+});
+
+// a link to the page with a crate in its hash resolves it straight away.
+const initial = new URLSearchParams(window.location.hash.slice(1));
+if (initial.get("name") && initial.get("version")) {
+    elements.name.value = initial.get("name");
+    elements.version.value = initial.get("version");
+    elements.depth.value = initial.get("depth") || "";
+    elements.form.requestSubmit();
+}
@@ -0,0 +1,22 @@
+use crate::domain::{Crate, CrateDependency, CrateMetadata, DependencyKind};
+use semver::Version;
+
+/// A crate without metadata, depending normally and non optionally on every crate+version of
+/// `dependency`.
+pub(crate) fn node(name: &str, version: &str, dependency: &[(&str, &str)]) -> Crate {
+    Crate {
+        name: name.to_owned(),
+        version: Version::parse(version).unwrap(),
+        dependency: dependency
+            .iter()
+            .map(|(name, version)| CrateDependency {
+                name: (*name).to_owned(),
+                version: Version::parse(version).unwrap(),
+                req: None,
+                kind: DependencyKind::Normal,
+                optional: false,
+            })
+            .collect(),
+        metadata: CrateMetadata::default(),
+    }
+}